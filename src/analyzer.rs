@@ -0,0 +1,728 @@
+//! Configurable rules for turning text into BBOW keys.
+//!
+//! An [Analyzer] is the [Tokenizer] used by a [Bbow] for
+//! [Bbow::extend_from_text]. Its default settings give the
+//! same words as [WhitespaceLetterTokenizer]; each setting
+//! can be changed to alter what counts as a word or how
+//! words are turned into keys.
+//!
+//! [Bbow]: crate::Bbow
+//! [Bbow::extend_from_text]: crate::Bbow::extend_from_text
+//! [WhitespaceLetterTokenizer]: crate::WhitespaceLetterTokenizer
+
+use std::borrow::Cow;
+use std::ops::Range;
+use std::sync::Arc;
+
+use unicode_normalization::char::is_combining_mark;
+
+use crate::case::apply_case;
+use crate::normalize::{normalize, strip_diacritics};
+use crate::stem::stem;
+use crate::tokenizer::{is_number, is_word, trim_punctuation, unicode_segments};
+use crate::{Case, Language, LemmaTable, Normalization, StopWords, Tokenizer};
+
+/// How text is split into candidate words.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Segmentation {
+    /// Split at whitespace, as [WhitespaceLetterTokenizer]
+    /// does.
+    ///
+    /// [WhitespaceLetterTokenizer]: crate::WhitespaceLetterTokenizer
+    #[default]
+    Whitespace,
+    /// Split at Unicode word boundaries, as
+    /// [UnicodeWordTokenizer] does.
+    ///
+    /// [UnicodeWordTokenizer]: crate::UnicodeWordTokenizer
+    UnicodeWords,
+}
+
+/// What to do with a word containing internal apostrophes
+/// or hyphens, such as `"ain't"` or `"well-known"`.
+///
+/// Both the ASCII apostrophe `'` and the right single
+/// quotation mark `’` are treated as apostrophes. Hyphens
+/// are the ASCII `-`, `‐` and the non-breaking `‑`.
+///
+/// With [Segmentation::UnicodeWords], hyphens always
+/// separate words, so this policy only affects apostrophes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InternalPunctuation {
+    /// Drop the whole word.
+    #[default]
+    Drop,
+    /// Keep the word as a single key. Curly apostrophes in
+    /// the key are replaced by `'`.
+    Keep,
+    /// Count each of the parts between the punctuation as
+    /// a separate word.
+    Split,
+}
+
+/// Which characters a word may be made of.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TokenClass {
+    /// Letters only: words containing digits are dropped.
+    #[default]
+    Letters,
+    /// Any mix of letters and digits, such as `"covid19"`,
+    /// `"mp3"` or `"3rd"`, as well as numbers.
+    Alphanumeric,
+    /// Words made only of letters, and numbers made only
+    /// of digits, such as `"2024"`; mixtures such as
+    /// `"mp3"` are dropped.
+    LettersAndNumbers,
+}
+
+impl TokenClass {
+    /// Returns the &str with any leading or trailing
+    /// characters that cannot be part of a word removed.
+    fn trim(self, word: &str) -> &str {
+        match self {
+            TokenClass::Letters => trim_punctuation(word),
+            _ => word.trim_matches(|c: char| !c.is_alphanumeric()),
+        }
+    }
+
+    /// Returns a bool indicating whether the &str is a word
+    /// of this class.
+    fn accepts(self, word: &str) -> bool {
+        match self {
+            TokenClass::Letters => is_word(word),
+            TokenClass::Alphanumeric => {
+                is_number(word) || (!word.is_empty() && word.chars().all(char::is_alphanumeric))
+            }
+            TokenClass::LettersAndNumbers => is_word(word) || is_number(word),
+        }
+    }
+}
+
+#[test]
+fn test_token_class() {
+    assert_eq!(TokenClass::Letters.trim("covid19!"), "covid");
+    assert_eq!(TokenClass::Alphanumeric.trim("(covid19)!"), "covid19");
+    assert!(!TokenClass::Letters.accepts("mp3"));
+    assert!(TokenClass::Alphanumeric.accepts("mp3"));
+    assert!(TokenClass::Alphanumeric.accepts("3.14"));
+    assert!(!TokenClass::LettersAndNumbers.accepts("mp3"));
+    assert!(TokenClass::LettersAndNumbers.accepts("2024"));
+    assert!(TokenClass::LettersAndNumbers.accepts("mp"));
+}
+
+#[test]
+fn test_collapse_numbers() {
+    let analyzer = Analyzer::new().collapse_numbers(true);
+    let words: Vec<_> = analyzer.tokenize("Chapter Ⅻ").collect();
+    assert_eq!(words, vec!["chapter", "ⅻ"]);
+    let analyzer = analyzer.token_class(TokenClass::LettersAndNumbers);
+    let words: Vec<_> = analyzer.tokenize("Chapter 12 Ⅻ").collect();
+    assert_eq!(words, vec!["chapter", NUMBER_PLACEHOLDER, "ⅻ"]);
+}
+
+/// The key that numbers are counted under when
+/// [Analyzer::collapse_numbers] is set.
+pub const NUMBER_PLACEHOLDER: &str = "<NUM>";
+
+/// A configurable [Tokenizer].
+///
+/// Settings are changed with builder methods, and the
+/// analyzer given to [Bbow::with_analyzer] to be used by
+/// [Bbow::extend_from_text]. The other bags in this crate,
+/// such as [NgramBbow](crate::NgramBbow) and
+/// [Concordance](crate::Concordance), find words with an
+/// analyzer in exactly the same way.
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::{Analyzer, Bbow, InternalPunctuation};
+/// let analyzer = Analyzer::new()
+///     .internal_punctuation(InternalPunctuation::Split);
+/// let bbow = Bbow::with_analyzer(analyzer)
+///     .extend_from_text("b b b-banana b");
+/// assert_eq!(4, bbow.match_count("b"));
+/// assert_eq!(1, bbow.match_count("banana"));
+/// ```
+///
+/// [Bbow::with_analyzer]: crate::Bbow::with_analyzer
+/// [Bbow::extend_from_text]: crate::Bbow::extend_from_text
+#[derive(Debug, Default, Clone)]
+pub struct Analyzer {
+    segmentation: Segmentation,
+    internal_punctuation: InternalPunctuation,
+    normalization: Normalization,
+    case: Case,
+    fold_diacritics: bool,
+    token_class: TokenClass,
+    collapse_numbers: bool,
+    stemmer: Option<Language>,
+    lemmas: Option<Arc<LemmaTable>>,
+    stop_words: Option<Arc<StopWords>>,
+}
+
+impl Analyzer {
+    /// Make a new analyzer with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how text is split into candidate words.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Analyzer, Bbow, Segmentation};
+    /// let analyzer = Analyzer::new().segmentation(Segmentation::UnicodeWords);
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text("hello,world");
+    /// assert_eq!(2, bbow.len());
+    /// ```
+    pub fn segmentation(mut self, segmentation: Segmentation) -> Self {
+        self.segmentation = segmentation;
+        self
+    }
+
+    /// Set what to do with words containing internal
+    /// apostrophes or hyphens.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Analyzer, Bbow, InternalPunctuation};
+    /// let text = "It ain't well-known, it ain’t.";
+    ///
+    /// let bbow = Bbow::new().extend_from_text(text);
+    /// assert_eq!(0, bbow.match_count("ain't"));
+    ///
+    /// let analyzer = Analyzer::new().internal_punctuation(InternalPunctuation::Keep);
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text(text);
+    /// assert_eq!(2, bbow.match_count("ain't"));
+    /// assert_eq!(1, bbow.match_count("well-known"));
+    ///
+    /// let analyzer = Analyzer::new().internal_punctuation(InternalPunctuation::Split);
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text(text);
+    /// assert_eq!(2, bbow.match_count("ain"));
+    /// assert_eq!(1, bbow.match_count("known"));
+    /// ```
+    pub fn internal_punctuation(mut self, policy: InternalPunctuation) -> Self {
+        self.internal_punctuation = policy;
+        self
+    }
+
+    /// Set the Unicode normalization form applied to text
+    /// before finding words, and to keywords given to
+    /// [Bbow::match_count].
+    ///
+    /// Words that are already normalized are still
+    /// borrowed from the source text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Analyzer, Bbow, Normalization};
+    /// let text = "café cafe\u{301}";
+    ///
+    /// let bbow = Bbow::new().extend_from_text(text);
+    /// assert_eq!(1, bbow.match_count("café"));
+    ///
+    /// let analyzer = Analyzer::new().normalization(Normalization::Nfc);
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text(text);
+    /// assert_eq!(2, bbow.match_count("café"));
+    /// assert_eq!(2, bbow.match_count("cafe\u{301}"));
+    /// ```
+    ///
+    /// [Bbow::match_count]: crate::Bbow::match_count
+    pub fn normalization(mut self, form: Normalization) -> Self {
+        self.normalization = form;
+        self
+    }
+
+    /// Set how the case of words is handled. Keywords given
+    /// to [Bbow::match_count] are handled in the same way,
+    /// except with the default [Case::Lowercase], where
+    /// keywords should already be lowercase.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Analyzer, Bbow, Case, CaseLocale};
+    /// let text = "Straße STRASSE";
+    ///
+    /// let bbow = Bbow::new().extend_from_text(text);
+    /// assert_eq!(1, bbow.match_count("straße"));
+    ///
+    /// let analyzer = Analyzer::new().case(Case::Fold);
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text(text);
+    /// assert_eq!(2, bbow.match_count("Straße"));
+    ///
+    /// let analyzer = Analyzer::new().case(Case::Locale(CaseLocale::Turkish));
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text("ILIK ılık");
+    /// assert_eq!(2, bbow.match_count("ılık"));
+    ///
+    /// let analyzer = Analyzer::new().case(Case::Sensitive);
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text("Hello hello");
+    /// assert_eq!(1, bbow.match_count("Hello"));
+    /// ```
+    ///
+    /// [Bbow::match_count]: crate::Bbow::match_count
+    pub fn case(mut self, case: Case) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Set whether diacritics are removed from words, so
+    /// that for example `"untïl"` is counted as `"until"`.
+    /// Keywords given to [Bbow::match_count] have their
+    /// diacritics removed in the same way.
+    ///
+    /// Words are decomposed, have their combining marks
+    /// dropped, and are recomposed. Letters with no
+    /// decomposition, such as `ø` or `ł`, are unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Analyzer, Bbow};
+    /// let text = "It ain't over untïl it ain't, over.";
+    ///
+    /// let bbow = Bbow::new().extend_from_text(text);
+    /// assert_eq!(0, bbow.match_count("until"));
+    ///
+    /// let analyzer = Analyzer::new().fold_diacritics(true);
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text(text);
+    /// assert_eq!(1, bbow.match_count("until"));
+    /// assert_eq!(1, bbow.match_count("untïl"));
+    /// ```
+    ///
+    /// [Bbow::match_count]: crate::Bbow::match_count
+    pub fn fold_diacritics(mut self, fold: bool) -> Self {
+        self.fold_diacritics = fold;
+        self
+    }
+
+    /// Set which characters a word may be made of.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Analyzer, Bbow, TokenClass};
+    /// let text = "In 2024, covid19 mp3s.";
+    ///
+    /// let analyzer = Analyzer::new().token_class(TokenClass::Alphanumeric);
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text(text);
+    /// assert_eq!(1, bbow.match_count("2024"));
+    /// assert_eq!(1, bbow.match_count("covid19"));
+    ///
+    /// let analyzer = Analyzer::new().token_class(TokenClass::LettersAndNumbers);
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text(text);
+    /// assert_eq!(1, bbow.match_count("2024"));
+    /// assert_eq!(0, bbow.match_count("covid19"));
+    /// ```
+    pub fn token_class(mut self, class: TokenClass) -> Self {
+        self.token_class = class;
+        self
+    }
+
+    /// Set whether all numbers are counted under the single
+    /// key [NUMBER_PLACEHOLDER]. This has no effect unless
+    /// the [TokenClass] allows numbers. Numbers given to
+    /// [Bbow::match_count] are also collapsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Analyzer, Bbow, TokenClass, NUMBER_PLACEHOLDER};
+    /// let analyzer = Analyzer::new()
+    ///     .token_class(TokenClass::LettersAndNumbers)
+    ///     .collapse_numbers(true);
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text("1 fish, 2 fish, 3.5 fish");
+    /// assert_eq!(3, bbow.match_count(NUMBER_PLACEHOLDER));
+    /// assert_eq!(3, bbow.match_count("42"));
+    /// assert_eq!(2, bbow.len());
+    /// ```
+    ///
+    /// [Bbow::match_count]: crate::Bbow::match_count
+    pub fn collapse_numbers(mut self, collapse: bool) -> Self {
+        self.collapse_numbers = collapse;
+        self
+    }
+
+    /// Set the language used to reduce words to their stems,
+    /// or `None` to count words as they are. Keywords given
+    /// to [Bbow::match_count] are stemmed in the same way.
+    ///
+    /// Stemming happens after case handling and before
+    /// diacritic folding, since the stemmers for many
+    /// languages rely on accents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Analyzer, Bbow, Language};
+    /// let analyzer = Analyzer::new().stemmer(Some(Language::English));
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text("Run! She runs, running.");
+    /// assert_eq!(3, bbow.match_count("run"));
+    /// assert_eq!(3, bbow.match_count("running"));
+    /// assert_eq!(2, bbow.len());
+    /// ```
+    ///
+    /// [Bbow::match_count]: crate::Bbow::match_count
+    pub fn stemmer(mut self, language: Option<Language>) -> Self {
+        self.stemmer = language;
+        self
+    }
+
+    /// Set a table of canonical forms: words found in the
+    /// table are counted under their canonical form.
+    /// Keywords given to [Bbow::match_count] are mapped in
+    /// the same way.
+    ///
+    /// Words are looked up after case handling, and the
+    /// canonical form is then stemmed if a stemmer is set.
+    /// A table shared between several analyzers can be given
+    /// as an [Arc].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Analyzer, Bbow, LemmaTable};
+    /// let lemmas = LemmaTable::read_tsv("colour\tcolor\n".as_bytes()).unwrap();
+    /// let analyzer = Analyzer::new().lemmas(lemmas);
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text("Colour or color?");
+    /// assert_eq!(2, bbow.match_count("color"));
+    /// assert_eq!(2, bbow.match_count("colour"));
+    /// ```
+    ///
+    /// [Bbow::match_count]: crate::Bbow::match_count
+    pub fn lemmas<T: Into<Arc<LemmaTable>>>(mut self, table: T) -> Self {
+        self.lemmas = Some(table.into());
+        self
+    }
+
+    /// Set a group of stopwords: words in the set are
+    /// skipped, and never stored in a [Bbow].
+    ///
+    /// Words are checked after case handling, and before
+    /// they are mapped to canonical forms or stemmed. A set
+    /// shared between several analyzers can be given as an
+    /// [Arc].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Analyzer, Bbow, Language, StopWords};
+    /// let stop_words = StopWords::for_language(Language::English).unwrap();
+    /// let analyzer = Analyzer::new().stop_words(stop_words);
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text("The end of the story.");
+    /// assert_eq!(0, bbow.match_count("the"));
+    /// assert_eq!(2, bbow.len());
+    /// ```
+    ///
+    /// [Bbow]: crate::Bbow
+    pub fn stop_words<T: Into<Arc<StopWords>>>(mut self, stop_words: T) -> Self {
+        self.stop_words = Some(stop_words.into());
+        self
+    }
+
+    /// Return the key that `keyword` is counted under, for
+    /// looking up a keyword supplied by the user, or `None`
+    /// if the keyword is a stopword.
+    ///
+    /// With the default [Case::Lowercase] the keyword's case
+    /// is left as it is, so that keys added by other
+    /// tokenizers that keep uppercase letters can be found.
+    pub(crate) fn query<'k>(&self, keyword: &'k str) -> Option<Cow<'k, str>> {
+        self.key(normalize(self.normalization, keyword), self.query_case())
+    }
+
+    /// Return `fragment`, part of a word supplied by the
+    /// user, prepared as for [Analyzer::query] by the steps
+    /// that apply to each character: normalization,
+    /// apostrophes, case and diacritics.
+    pub(crate) fn query_fragment<'k>(&self, fragment: &'k str) -> Cow<'k, str> {
+        let fragment = map_cow(
+            normalize(self.normalization, fragment),
+            straighten_apostrophes,
+        );
+        let fragment = map_cow(fragment, |f| apply_case(self.query_case(), f));
+        if self.fold_diacritics {
+            map_cow(fragment, strip_diacritics)
+        } else {
+            fragment
+        }
+    }
+
+    /// The case handling applied to queries.
+    fn query_case(&self) -> Case {
+        match self.case {
+            Case::Lowercase => Case::Sensitive,
+            case => case,
+        }
+    }
+
+    /// Return the sequence of words contained in `text`,
+    /// each with the byte range of `text` it was found in.
+    ///
+    /// When normalization changes a candidate word, the
+    /// words found in it are each given the range of the
+    /// whole candidate, less any leading and trailing
+    /// punctuation other than combining marks.
+    pub(crate) fn spans<'s, 't: 's>(
+        &'s self,
+        text: &'t str,
+    ) -> Box<dyn Iterator<Item = (Range<usize>, Cow<'t, str>)> + 's> {
+        self.spans_with_case(text, self.case)
+    }
+
+    /// Return the sequence of keys of the words in
+    /// `phrase`, a phrase supplied by the user, with case
+    /// handled as for [Analyzer::query].
+    pub(crate) fn query_words<'s, 't: 's>(
+        &'s self,
+        phrase: &'t str,
+    ) -> impl Iterator<Item = Cow<'t, str>> + 's {
+        self.spans_with_case(phrase, self.query_case())
+            .map(|(_, w)| w)
+    }
+
+    /// Return the words contained in `text` with their
+    /// byte ranges, as for [Analyzer::spans], handling case
+    /// as given.
+    fn spans_with_case<'s, 't: 's>(
+        &'s self,
+        text: &'t str,
+        case: Case,
+    ) -> Box<dyn Iterator<Item = (Range<usize>, Cow<'t, str>)> + 's> {
+        let candidates: Box<dyn Iterator<Item = &'t str>> = match self.segmentation {
+            Segmentation::Whitespace => Box::new(text.split_whitespace()),
+            Segmentation::UnicodeWords => Box::new(unicode_segments(text)),
+        };
+        Box::new(
+            candidates
+                .flat_map(move |w| self.words(text, w))
+                .filter_map(move |(span, w)| Some((span, self.key(w, case)?))),
+        )
+    }
+
+    /// Return the words contained in a candidate word
+    /// within `text`, normalizing it first if required.
+    fn words<'t>(&self, text: &'t str, candidate: &'t str) -> Words<'t> {
+        match normalize(self.normalization, candidate) {
+            Cow::Borrowed(candidate) => Words::Borrowed {
+                text,
+                pieces: self.pieces(self.token_class.trim(candidate)),
+            },
+            Cow::Owned(normalized) => {
+                let pieces = self.pieces(self.token_class.trim(&normalized));
+                Words::Owned {
+                    span: marked_span(text, candidate, self.token_class.trim(candidate)),
+                    pieces: pieces.map(String::from).collect::<Vec<_>>().into_iter(),
+                }
+            }
+        }
+    }
+
+    /// Return the words contained in a candidate word that
+    /// has had leading and trailing punctuation removed.
+    fn pieces<'t>(&self, candidate: &'t str) -> Pieces<'t> {
+        let class = self.token_class;
+        match self.internal_punctuation {
+            InternalPunctuation::Drop => Pieces::One(Some(candidate).filter(|w| class.accepts(w))),
+            InternalPunctuation::Keep => Pieces::One(
+                Some(candidate).filter(|w| w.split(is_joiner).all(|p| class.accepts(p))),
+            ),
+            InternalPunctuation::Split => Pieces::Split(candidate.split(is_joiner), class),
+        }
+    }
+
+    /// Turn a word into the key it is counted under, or
+    /// return `None` if the word is a stopword.
+    fn key<'t>(&self, word: Cow<'t, str>, case: Case) -> Option<Cow<'t, str>> {
+        if self.collapse_numbers
+            && self.token_class != TokenClass::Letters
+            && (is_number(&word) || word == NUMBER_PLACEHOLDER)
+        {
+            return Some(Cow::from(NUMBER_PLACEHOLDER));
+        }
+        let word = map_cow(word, straighten_apostrophes);
+        let word = map_cow(word, |w| apply_case(case, w));
+        if self
+            .stop_words
+            .as_ref()
+            .is_some_and(|stop_words| stop_words.contains(&word))
+        {
+            return None;
+        }
+        let word = match &self.lemmas {
+            Some(lemmas) => map_cow(word, |w| lemmas.canonicalize(w)),
+            None => word,
+        };
+        let word = match self.stemmer {
+            Some(language) => map_cow(word, |w| stem(language, w)),
+            None => word,
+        };
+        if self.fold_diacritics {
+            Some(map_cow(word, strip_diacritics))
+        } else {
+            Some(word)
+        }
+    }
+}
+
+impl Tokenizer for Analyzer {
+    fn tokenize<'s, 't: 's>(
+        &'s self,
+        text: &'t str,
+    ) -> Box<dyn Iterator<Item = Cow<'t, str>> + 's> {
+        Box::new(self.spans(text).map(|(_, w)| w))
+    }
+}
+
+/// The words found in a single candidate word, with their
+/// byte ranges in the source text. Words are borrowed from
+/// the source text unless normalization changed them.
+enum Words<'t> {
+    Borrowed {
+        text: &'t str,
+        pieces: Pieces<'t>,
+    },
+    Owned {
+        span: Range<usize>,
+        pieces: std::vec::IntoIter<String>,
+    },
+}
+
+impl<'t> Iterator for Words<'t> {
+    type Item = (Range<usize>, Cow<'t, str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Words::Borrowed { text, pieces } => {
+                let piece = pieces.next()?;
+                Some((span(text, piece), Cow::from(piece)))
+            }
+            Words::Owned { span, pieces } => Some((span.clone(), Cow::from(pieces.next()?))),
+        }
+    }
+}
+
+/// Returns the byte range of `text` covered by `slice`,
+/// which must be a slice of `text`.
+fn span(text: &str, slice: &str) -> Range<usize> {
+    let start = slice.as_ptr() as usize - text.as_ptr() as usize;
+    start..start + slice.len()
+}
+
+#[test]
+fn test_span() {
+    let text = "a word";
+    assert_eq!(span(text, &text[2..]), 2..6);
+}
+
+/// Returns the byte range of `text` covered by `trimmed`,
+/// a slice of `candidate` which is itself a slice of
+/// `text`, extended over any combining marks that follow
+/// it in `candidate`. Trimming removes these marks from an
+/// unnormalized word, although they belong to its last
+/// letter.
+fn marked_span(text: &str, candidate: &str, trimmed: &str) -> Range<usize> {
+    let Range { start, end } = span(text, trimmed);
+    let marks: usize = text[end..span(text, candidate).end]
+        .chars()
+        .take_while(|&c| is_combining_mark(c))
+        .map(char::len_utf8)
+        .sum();
+    start..end + marks
+}
+
+#[test]
+fn test_marked_span() {
+    let text = "(cafe\u{301}) x";
+    assert_eq!(marked_span(text, &text[..8], &text[1..5]), 1..7);
+
+    let bbow = crate::Bbow::with_analyzer(Analyzer::new().normalization(Normalization::Nfc))
+        .record_positions(true)
+        .extend_from_text("cafe\u{301} x");
+    let spans: Vec<_> = bbow.occurrences("café").map(|o| o.span.clone()).collect();
+    assert_eq!(spans, vec![0..6]);
+}
+
+/// The pieces of a single candidate word. Split
+/// pieces that are not words of the class are skipped.
+enum Pieces<'t> {
+    One(Option<&'t str>),
+    Split(std::str::Split<'t, fn(char) -> bool>, TokenClass),
+}
+
+impl<'t> Iterator for Pieces<'t> {
+    type Item = &'t str;
+
+    fn next(&mut self) -> Option<&'t str> {
+        match self {
+            Pieces::One(word) => word.take(),
+            Pieces::Split(pieces, class) => pieces.find(|w| class.accepts(w)),
+        }
+    }
+}
+
+/// Apply a transformation `f` to a word, keeping the word
+/// borrowed if it was borrowed and `f` leaves it unchanged.
+pub(crate) fn map_cow<'t, F>(word: Cow<'t, str>, f: F) -> Cow<'t, str>
+where
+    F: for<'w> Fn(&'w str) -> Cow<'w, str>,
+{
+    match word {
+        Cow::Borrowed(w) => f(w),
+        Cow::Owned(w) => match f(&w) {
+            Cow::Borrowed(unchanged) if unchanged.len() == w.len() => Cow::Owned(w),
+            changed => Cow::Owned(changed.into_owned()),
+        },
+    }
+}
+
+#[cfg(test)]
+use crate::tokenizer::lowercase;
+
+#[test]
+fn test_map_cow() {
+    assert!(matches!(
+        map_cow(Cow::from("word"), lowercase),
+        Cow::Borrowed("word")
+    ));
+    assert_eq!(map_cow(Cow::from("Word"), lowercase), "word");
+    assert_eq!(map_cow(Cow::from(String::from("Word")), lowercase), "word");
+}
+
+/// Returns a bool indicating whether the char is an
+/// apostrophe or hyphen that may join the parts of a word.
+fn is_joiner(c: char) -> bool {
+    matches!(c, '\'' | '\u{2019}' | '-' | '\u{2010}' | '\u{2011}')
+}
+
+#[test]
+fn test_is_joiner() {
+    assert!(is_joiner('\''));
+    assert!(is_joiner('’'));
+    assert!(is_joiner('-'));
+    assert!(!is_joiner('.'));
+    assert!(!is_joiner('a'));
+}
+
+/// Returns the &str with any curly apostrophes replaced by
+/// ASCII apostrophes, borrowing if there are none.
+fn straighten_apostrophes(word: &str) -> Cow<'_, str> {
+    if word.contains('\u{2019}') {
+        Cow::from(word.replace('\u{2019}', "'"))
+    } else {
+        Cow::from(word)
+    }
+}
+
+#[test]
+fn test_straighten_apostrophes() {
+    assert!(matches!(
+        straighten_apostrophes("ain't"),
+        Cow::Borrowed("ain't")
+    ));
+    assert_eq!(straighten_apostrophes("ain’t"), "ain't");
+}
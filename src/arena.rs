@@ -0,0 +1,117 @@
+//! Bags that own their texts.
+//!
+//! A [Bbow] borrows its keys from the texts added to it,
+//! so it cannot outlive them. An [ArenaBbow] instead copies
+//! each text into an append-only arena that it owns, and
+//! borrows its keys from there.
+
+use self_cell::self_cell;
+use typed_arena::Arena;
+
+use crate::{Analyzer, Bbow};
+
+type Words<'a> = Bbow<'a>;
+
+self_cell!(
+    struct Inner {
+        owner: Arena<u8>,
+
+        #[covariant]
+        dependent: Words,
+    }
+);
+
+/// A [Bbow] that owns the texts added to it.
+///
+/// Each text is copied once into an internal arena, and
+/// keys are borrowed from the copy exactly as a [Bbow]
+/// borrows from its texts, so no allocation is needed for
+/// most words. An `ArenaBbow` has no lifetime parameter
+/// and can be sent between threads.
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::ArenaBbow;
+/// fn count_words(texts: &[String]) -> ArenaBbow {
+///     texts
+///         .iter()
+///         .fold(ArenaBbow::new(), |bbow, text| bbow.extend_from_text(text))
+/// }
+///
+/// let bbow = count_words(&[String::from("hello world."), String::from("Hello!")]);
+/// let bbow = std::thread::spawn(move || bbow).join().unwrap();
+/// assert_eq!(2, bbow.bbow().match_count("hello"));
+/// assert_eq!(2, bbow.bbow().borrowed_keys());
+/// ```
+pub struct ArenaBbow(Inner);
+
+impl ArenaBbow {
+    /// Make a new empty target words list.
+    pub fn new() -> Self {
+        Self::with_analyzer(Analyzer::default())
+    }
+
+    /// Make a new empty target words list that uses the
+    /// given `analyzer` to find words, as for
+    /// [Bbow::with_analyzer].
+    pub fn with_analyzer(analyzer: Analyzer) -> Self {
+        Self(Inner::new(Arena::new(), |_| Bbow::with_analyzer(analyzer)))
+    }
+
+    /// Set whether this BBOW records the position of every
+    /// occurrence of each word, as for
+    /// [Bbow::record_positions].
+    pub fn record_positions(mut self, record: bool) -> Self {
+        self.update(|bbow, _| bbow.record_positions(record));
+        self
+    }
+
+    /// Copy the `target` text into this BBOW and add the
+    /// sequence of valid words contained in it, as for
+    /// [Bbow::extend_from_text].
+    ///
+    /// This is a builder method: calls can be chained.
+    pub fn extend_from_text(mut self, target: &str) -> Self {
+        self.update(|bbow, arena| bbow.extend_from_text(arena.alloc_str(target)));
+        self
+    }
+
+    /// Return the [Bbow] holding the words of this BBOW,
+    /// for counting and listing them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::ArenaBbow;
+    /// let bbow = ArenaBbow::new().extend_from_text("Hello world.");
+    /// let words: Vec<_> = bbow.bbow().words().collect();
+    /// assert_eq!(vec!["hello", "world"], words);
+    /// ```
+    pub fn bbow(&self) -> &Bbow<'_> {
+        self.0.borrow_dependent()
+    }
+
+    /// Replace the inner [Bbow] with the result of `f`,
+    /// which may add texts allocated in the arena.
+    fn update<F>(&mut self, f: F)
+    where
+        F: for<'a> FnOnce(Bbow<'a>, &'a Arena<u8>) -> Bbow<'a>,
+    {
+        self.0.with_dependent_mut(|arena, bbow| {
+            *bbow = f(std::mem::take(bbow), arena);
+        });
+    }
+}
+
+impl Default for ArenaBbow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for ArenaBbow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ArenaBbow").field(self.bbow()).finish()
+    }
+}
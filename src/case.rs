@@ -0,0 +1,149 @@
+//! Case handling for words.
+//!
+//! By default words are lowercased with
+//! [str::to_lowercase]. This is not always enough to make
+//! words that differ only in case share a key: `"Straße"`
+//! and `"STRASSE"` lowercase differently, and Turkish
+//! dotted and dotless `i` need language-specific rules.
+
+use std::borrow::Cow;
+
+use caseless::Caseless;
+use unicode_normalization::char::canonical_combining_class;
+
+use crate::tokenizer::{has_uppercase, lowercase};
+
+/// How the case of words is handled when turning them into
+/// keys.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// Lowercase words with [str::to_lowercase].
+    #[default]
+    Lowercase,
+    /// Apply Unicode full case folding, so that for example
+    /// `"Straße"` and `"STRASSE"` are both `"strasse"`, and
+    /// Greek final and non-final sigma are the same.
+    Fold,
+    /// Lowercase words using the rules of the given
+    /// language.
+    Locale(CaseLocale),
+    /// Leave words as they are.
+    Sensitive,
+}
+
+/// A language with special lowercasing rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseLocale {
+    /// Turkish (`tr`): `I` lowercases to dotless `ı`, and
+    /// `İ` to `i`.
+    Turkish,
+    /// Azerbaijani (`az`): the same rules as Turkish.
+    Azerbaijani,
+    /// Lithuanian (`lt`): a dot above is kept on a
+    /// lowercased `i` or `j` that has another accent above.
+    Lithuanian,
+}
+
+/// Returns the &str with its case handled as given,
+/// borrowing if it is unchanged.
+pub(crate) fn apply_case(case: Case, word: &str) -> Cow<'_, str> {
+    match case {
+        Case::Lowercase => lowercase(word),
+        Case::Fold => fold_case(word),
+        Case::Locale(CaseLocale::Turkish | CaseLocale::Azerbaijani) => turkic_lowercase(word),
+        Case::Locale(CaseLocale::Lithuanian) => lithuanian_lowercase(word),
+        Case::Sensitive => Cow::from(word),
+    }
+}
+
+#[test]
+fn test_apply_case() {
+    assert!(matches!(
+        apply_case(Case::Fold, "word"),
+        Cow::Borrowed("word")
+    ));
+    assert_eq!(apply_case(Case::Lowercase, "Straße"), "straße");
+    assert_eq!(apply_case(Case::Fold, "Straße"), "strasse");
+    assert_eq!(apply_case(Case::Fold, "STRASSE"), "strasse");
+    assert_eq!(
+        apply_case(Case::Fold, "ΟΔΟΣ"),
+        apply_case(Case::Fold, "οδος")
+    );
+    assert_eq!(apply_case(Case::Sensitive, "Word"), "Word");
+}
+
+/// Returns the Unicode full case folding of the &str.
+fn fold_case(word: &str) -> Cow<'_, str> {
+    if word.chars().default_case_fold().eq(word.chars()) {
+        Cow::from(word)
+    } else {
+        Cow::from(word.chars().default_case_fold().collect::<String>())
+    }
+}
+
+/// Returns the &str lowercased using the Turkish and
+/// Azerbaijani rules from the Unicode `SpecialCasing.txt`.
+fn turkic_lowercase(word: &str) -> Cow<'_, str> {
+    if !has_uppercase(word) {
+        return Cow::from(word);
+    }
+    let mut lower = String::with_capacity(word.len());
+    let mut chars = word.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            // An I with a combining dot above is a plain i.
+            'I' if chars.peek() == Some(&'\u{307}') => {
+                chars.next();
+                lower.push('i');
+            }
+            'I' => lower.push('ı'),
+            'İ' => lower.push('i'),
+            _ => lower.push(c),
+        }
+    }
+    // The remaining characters lowercase as usual,
+    // including the context-dependent final sigma.
+    Cow::from(lower.to_lowercase())
+}
+
+#[test]
+fn test_turkic_lowercase() {
+    assert_eq!(turkic_lowercase("DİYARBAKIR"), "diyarbakır");
+    assert_eq!(turkic_lowercase("I\u{307}"), "i");
+    assert!(matches!(turkic_lowercase("ılık"), Cow::Borrowed("ılık")));
+}
+
+/// Returns the &str lowercased using the Lithuanian rules
+/// from the Unicode `SpecialCasing.txt`.
+fn lithuanian_lowercase(word: &str) -> Cow<'_, str> {
+    if !has_uppercase(word) {
+        return Cow::from(word);
+    }
+    let mut lower = String::with_capacity(word.len());
+    let mut chars = word.chars().peekable();
+    while let Some(c) = chars.next() {
+        // The combining class of accents placed above a
+        // letter.
+        let more_above = chars
+            .peek()
+            .is_some_and(|&next| canonical_combining_class(next) == 230);
+        match c {
+            'I' | 'J' | 'Į' if more_above => {
+                lower.extend(c.to_lowercase());
+                lower.push('\u{307}');
+            }
+            'Ì' => lower.push_str("i\u{307}\u{300}"),
+            'Í' => lower.push_str("i\u{307}\u{301}"),
+            'Ĩ' => lower.push_str("i\u{307}\u{303}"),
+            _ => lower.push(c),
+        }
+    }
+    Cow::from(lower.to_lowercase())
+}
+
+#[test]
+fn test_lithuanian_lowercase() {
+    assert_eq!(lithuanian_lowercase("Ì"), "i\u{307}\u{300}");
+    assert_eq!(lithuanian_lowercase("J\u{303}"), "j\u{307}\u{303}");
+    assert_eq!(lithuanian_lowercase("IJ"), "ij");
+}
@@ -0,0 +1,236 @@
+//! Bags of character n-grams.
+//!
+//! A character n-gram is a sequence of `n` consecutive
+//! characters within a word: the 3-grams of `"word"` are
+//! `"wor"` and `"ord"`. Counts of character n-grams are
+//! useful for spelling similarity and language detection.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::ops::{Range, RangeInclusive};
+
+use crate::{Analyzer, Tokenizer};
+
+/// The marker placed before the start of a padded word.
+pub const WORD_START: char = '<';
+
+/// The marker placed after the end of a padded word.
+pub const WORD_END: char = '>';
+
+/// Each key in this struct's map is a sequence of
+/// consecutive characters within a word in some in-memory
+/// text document. The corresponding value is the count of
+/// occurrences.
+///
+/// N-grams of every length in a chosen range are counted
+/// in the words found by an [Analyzer]. N-grams do not span
+/// separate words. When a word
+/// is borrowed from the source text, so are its n-grams.
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::CharNgramBbow;
+/// let ngrams = CharNgramBbow::new(3..=3).extend_from_text("Banana!");
+/// assert_eq!(2, ngrams.match_count("ana"));
+/// assert_eq!(1, ngrams.match_count("ban"));
+/// assert_eq!(3, ngrams.len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct CharNgramBbow<'a> {
+    ngrams: BTreeMap<Cow<'a, str>, usize>,
+    analyzer: Analyzer,
+    n: RangeInclusive<usize>,
+    padded: bool,
+}
+
+impl<'a> CharNgramBbow<'a> {
+    /// Make a new empty n-gram list counting n-grams with
+    /// lengths in the range `n`, using the default
+    /// [Analyzer] settings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is empty or includes 0.
+    pub fn new(n: RangeInclusive<usize>) -> Self {
+        Self::with_analyzer(Analyzer::default(), n)
+    }
+
+    /// Make a new empty n-gram list counting n-grams with
+    /// lengths in the range `n`, using the given `analyzer`
+    /// to find words.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is empty or includes 0.
+    pub fn with_analyzer(analyzer: Analyzer, n: RangeInclusive<usize>) -> Self {
+        assert!(
+            !n.is_empty() && *n.start() > 0,
+            "n-gram lengths must be at least 1"
+        );
+        Self {
+            ngrams: BTreeMap::new(),
+            analyzer,
+            n,
+            padded: false,
+        }
+    }
+
+    /// Set whether words are padded with [WORD_START] and
+    /// [WORD_END] before finding n-grams, so that n-grams at
+    /// the start and end of words are distinguished. This
+    /// should be set before any text is added.
+    ///
+    /// Padded n-grams are owned strings; n-grams inside a
+    /// word are still borrowed. A marker alone is not
+    /// counted as a 1-gram.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::CharNgramBbow;
+    /// let ngrams = CharNgramBbow::new(3..=3)
+    ///     .padded(true)
+    ///     .extend_from_text("word");
+    /// let ngrams: Vec<_> = ngrams.ngrams().collect();
+    /// assert_eq!(vec!["<wo", "ord", "rd>", "wor"], ngrams);
+    /// ```
+    pub fn padded(mut self, padded: bool) -> Self {
+        self.padded = padded;
+        self
+    }
+
+    /// Parse the `target` text and add the character
+    /// n-grams of the valid words contained in it to this
+    /// list.
+    ///
+    /// This is a builder method: calls can be chained.
+    pub fn extend_from_text(mut self, target: &'a str) -> Self {
+        for word in self.analyzer.tokenize(target) {
+            let bounds: Vec<_> = word
+                .char_indices()
+                .map(|(i, _)| i)
+                .chain([word.len()])
+                .collect();
+            // No n-gram is longer than the padded word.
+            let longest = bounds.len() - 1 + 2 * usize::from(self.padded);
+            for n in *self.n.start()..=(*self.n.end()).min(longest) {
+                for gram in char_ngrams(&word, &bounds, n, self.padded) {
+                    *self.ngrams.entry(gram).or_insert(0) += 1;
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Report the number of occurrences of the given
+    /// n-gram that are indexed by this list. As the n-grams
+    /// are taken from the keys of words, the n-gram should
+    /// normally be lowercase.
+    pub fn match_count(&self, ngram: &str) -> usize {
+        self.ngrams.get(ngram).copied().unwrap_or(0)
+    }
+
+    /// Return the unique n-grams from the sampled text(s).
+    pub fn ngrams(&self) -> impl Iterator<Item = &str> {
+        self.ngrams.keys().map(|g| g.as_ref())
+    }
+
+    /// Count the overall number of n-grams contained in
+    /// this list: multiple occurrences are considered
+    /// separate.
+    pub fn count(&self) -> usize {
+        self.ngrams.values().sum()
+    }
+
+    /// Count the number of unique n-grams contained in this
+    /// list, not considering number of occurrences.
+    pub fn len(&self) -> usize {
+        self.ngrams.len()
+    }
+
+    /// Is this list empty?
+    pub fn is_empty(&self) -> bool {
+        self.ngrams.is_empty()
+    }
+}
+
+/// Returns the character n-grams of length `n` in `word`,
+/// whose char boundaries (including its end) are `bounds`.
+/// If `padded` is set, the word is treated as if surrounded
+/// by [WORD_START] and [WORD_END], and n-grams holding only
+/// a marker are skipped.
+fn char_ngrams<'a, 'w>(
+    word: &'w Cow<'a, str>,
+    bounds: &'w [usize],
+    n: usize,
+    padded: bool,
+) -> impl Iterator<Item = Cow<'a, str>> + 'w {
+    // The number of chars in the word, and in the possibly
+    // padded sequence the n-grams are taken from.
+    let chars = bounds.len() - 1;
+    let pad = usize::from(padded);
+    let length = chars + 2 * pad;
+    (0..(length + 1).saturating_sub(n)).filter_map(move |start| {
+        // The n-gram's range of chars within the word.
+        let first = start.saturating_sub(pad);
+        let last = (start + n - pad).min(chars);
+        let inner = bounds[first]..bounds[last];
+        if inner.is_empty() {
+            return None;
+        }
+        let at_start = padded && start == 0;
+        let at_end = padded && start + n == length;
+        if !at_start && !at_end {
+            return Some(slice(word, inner));
+        }
+        let mut gram = String::with_capacity(inner.len() + 2);
+        if at_start {
+            gram.push(WORD_START);
+        }
+        gram.push_str(&word[inner]);
+        if at_end {
+            gram.push(WORD_END);
+        }
+        Some(Cow::from(gram))
+    })
+}
+
+/// Returns a slice of the word, borrowed if the word is.
+fn slice<'a>(word: &Cow<'a, str>, range: Range<usize>) -> Cow<'a, str> {
+    match word {
+        Cow::Borrowed(w) => Cow::Borrowed(&w[range]),
+        Cow::Owned(w) => Cow::Owned(w[range].to_string()),
+    }
+}
+
+#[test]
+fn test_char_ngrams() {
+    let word = Cow::from("añb");
+    let bounds = [0, 1, 3, 4];
+    let grams: Vec<_> = char_ngrams(&word, &bounds, 2, false).collect();
+    assert_eq!(grams, vec!["añ", "ñb"]);
+    assert!(grams.iter().all(|g| matches!(g, Cow::Borrowed(_))));
+
+    let grams: Vec<_> = char_ngrams(&word, &bounds, 2, true).collect();
+    assert_eq!(grams, vec!["<a", "añ", "ñb", "b>"]);
+
+    let grams: Vec<_> = char_ngrams(&word, &bounds, 5, true).collect();
+    assert_eq!(grams, vec!["<añb>"]);
+
+    let grams: Vec<_> = char_ngrams(&word, &bounds, 1, true).collect();
+    assert_eq!(grams, vec!["a", "ñ", "b"]);
+    assert_eq!(char_ngrams(&word, &bounds, 4, false).count(), 0);
+}
+
+#[test]
+fn test_longer_than_word() {
+    let ngrams = CharNgramBbow::new(3..=usize::MAX)
+        .padded(true)
+        .extend_from_text("ab cd");
+    assert_eq!(
+        ngrams.ngrams().collect::<Vec<_>>(),
+        vec!["<ab", "<ab>", "<cd", "<cd>", "ab>", "cd>"]
+    );
+}
@@ -0,0 +1,245 @@
+//! Keyword-in-context (KWIC) concordances.
+//!
+//! A concordance lists every occurrence of a keyword in a
+//! text together with a few words of context on either
+//! side, so that the ways the keyword is used can be
+//! compared at a glance.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::Analyzer;
+
+/// The order of the lines of a [Concordance].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ContextOrder {
+    /// The order the occurrences appear in the text.
+    #[default]
+    Text,
+    /// Alphabetical order of the left context, comparing
+    /// the nearest word to the keyword first.
+    Left,
+    /// Alphabetical order of the right context, comparing
+    /// the nearest word to the keyword first.
+    Right,
+}
+
+/// A single line of a [Concordance]. All parts are slices
+/// of the original text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KwicLine<'a> {
+    /// The text before the keyword, starting at the first
+    /// word of context.
+    pub left: &'a str,
+    /// The keyword as it appears in the text.
+    pub keyword: &'a str,
+    /// The text after the keyword, ending at the last word
+    /// of context.
+    pub right: &'a str,
+    /// The byte range of the text covered by the keyword.
+    pub span: Range<usize>,
+}
+
+/// A keyword-in-context concordance over an in-memory text
+/// document.
+///
+/// Context is counted in the words found by an
+/// [Analyzer]. Skipped words, such as stopwords, still
+/// appear in the context text but are not counted.
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::{Concordance, ContextOrder};
+/// let text = "The cat sat. A dog sat on the mat, and the cat ran.";
+/// let concordance = Concordance::new(text).context(2).order(ContextOrder::Right);
+/// let lines = concordance.lines("cat");
+/// assert_eq!(2, lines.len());
+/// assert_eq!(("and the", "ran"), (lines[0].left, lines[0].right));
+/// assert_eq!(("The", "sat. A"), (lines[1].left, lines[1].right));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Concordance<'a> {
+    text: &'a str,
+    words: Vec<(Range<usize>, Cow<'a, str>)>,
+    analyzer: Analyzer,
+    context: usize,
+    order: ContextOrder,
+}
+
+impl<'a> Concordance<'a> {
+    /// Make a concordance over `text`, using the default
+    /// [Analyzer] settings.
+    pub fn new(text: &'a str) -> Self {
+        Self::with_analyzer(Analyzer::default(), text)
+    }
+
+    /// Make a concordance over `text`, using the given
+    /// `analyzer` to find words. Lines show five words of
+    /// context on each side, in text order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Analyzer, Bbow, Concordance, Language};
+    /// let text = "Connected, connecting: the connection.";
+    /// let bbow = Bbow::with_analyzer(Analyzer::new().stemmer(Some(Language::English)))
+    ///     .extend_from_text(text);
+    /// let concordance = Concordance::with_analyzer(bbow.analyzer().clone(), text);
+    /// let keywords: Vec<_> = concordance.lines("connect").iter().map(|l| l.keyword).collect();
+    /// assert_eq!(vec!["Connected", "connecting", "connection"], keywords);
+    /// ```
+    pub fn with_analyzer(analyzer: Analyzer, text: &'a str) -> Self {
+        Self {
+            text,
+            words: analyzer.spans(text).collect(),
+            analyzer,
+            context: 5,
+            order: ContextOrder::default(),
+        }
+    }
+
+    /// Set the number of words of context shown on each
+    /// side of the keyword.
+    pub fn context(mut self, words: usize) -> Self {
+        self.context = words;
+        self
+    }
+
+    /// Set the order of the lines.
+    pub fn order(mut self, order: ContextOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Return a line for each occurrence of `keyword`. The
+    /// keyword is prepared as for
+    /// [Bbow::match_count](crate::Bbow::match_count).
+    pub fn lines(&self, keyword: &str) -> Vec<KwicLine<'a>> {
+        let Some(key) = self.analyzer.query(keyword) else {
+            return Vec::new();
+        };
+        let mut hits: Vec<usize> = (0..self.words.len())
+            .filter(|&i| self.words[i].1 == key)
+            .collect();
+        // The sorts are stable, so ties stay in text order.
+        match self.order {
+            ContextOrder::Text => {}
+            ContextOrder::Left => hits.sort_by(|&a, &b| self.left_words(a).cmp(self.left_words(b))),
+            ContextOrder::Right => {
+                hits.sort_by(|&a, &b| self.right_words(a).cmp(self.right_words(b)))
+            }
+        }
+        hits.into_iter().map(|i| self.line(i)).collect()
+    }
+
+    /// Render the lines for `keyword` as plain text, one
+    /// line each, with the keywords aligned. Runs of
+    /// whitespace in the context are shown as single spaces.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Concordance;
+    /// let concordance = Concordance::new("A cat.\nThe black cat sat.").context(1);
+    /// assert_eq!(
+    ///     "    A  cat  . The\nblack  cat  sat\n",
+    ///     concordance.to_text("cat"),
+    /// );
+    /// ```
+    pub fn to_text(&self, keyword: &str) -> String {
+        let lines: Vec<_> = self
+            .lines(keyword)
+            .into_iter()
+            .map(|line| (collapse(line.left), line.keyword, collapse(line.right)))
+            .collect();
+        let width = lines
+            .iter()
+            .map(|(left, _, _)| left.chars().count())
+            .max()
+            .unwrap_or(0);
+        lines
+            .iter()
+            .map(|(left, keyword, right)| format!("{left:>width$}  {keyword}  {right}\n"))
+            .collect()
+    }
+
+    /// Render the lines for `keyword` as CSV, with a header
+    /// row and the columns `left`, `keyword` and `right`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Concordance;
+    /// let concordance = Concordance::new("Yes, \"cats\" purr.").context(1);
+    /// assert_eq!(
+    ///     "left,keyword,right\n\"Yes, \"\"\",cats,\"\"\" purr\"\n",
+    ///     concordance.to_csv("cats"),
+    /// );
+    /// ```
+    pub fn to_csv(&self, keyword: &str) -> String {
+        let mut csv = String::from("left,keyword,right\n");
+        for line in self.lines(keyword) {
+            let fields = [line.left, line.keyword, line.right].map(csv_field);
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Return the keys of the context words to the left of
+    /// the word at `index`, nearest first.
+    fn left_words(&self, index: usize) -> impl Iterator<Item = &str> {
+        self.words[index.saturating_sub(self.context)..index]
+            .iter()
+            .rev()
+            .map(|(_, w)| w.as_ref())
+    }
+
+    /// Return the keys of the context words to the right of
+    /// the word at `index`, nearest first.
+    fn right_words(&self, index: usize) -> impl Iterator<Item = &str> {
+        self.words[index + 1..]
+            .iter()
+            .take(self.context)
+            .map(|(_, w)| w.as_ref())
+    }
+
+    /// Make the line for the word at `index`.
+    fn line(&self, index: usize) -> KwicLine<'a> {
+        let span = self.words[index].0.clone();
+        let first = index.saturating_sub(self.context);
+        let last = (index + self.context).min(self.words.len() - 1);
+        let left = &self.text[self.words[first].0.start.min(span.start)..span.start];
+        let right = &self.text[span.end..self.words[last].0.end.max(span.end)];
+        KwicLine {
+            left: left.trim_end(),
+            keyword: &self.text[span.clone()],
+            right: right.trim_start(),
+            span,
+        }
+    }
+}
+
+/// Returns the text with each run of whitespace replaced by
+/// a single space.
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Returns the text as a CSV field, quoted if required.
+fn csv_field(text: &str) -> Cow<'_, str> {
+    if text.contains([',', '"', '\n', '\r']) {
+        Cow::from(format!("\"{}\"", text.replace('"', "\"\"")))
+    } else {
+        Cow::from(text)
+    }
+}
+
+#[test]
+fn test_csv_field() {
+    assert!(matches!(csv_field("plain text"), Cow::Borrowed(_)));
+    assert_eq!(csv_field("a, b"), "\"a, b\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+}
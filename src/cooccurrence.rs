@@ -0,0 +1,171 @@
+//! Counting words that occur near each other.
+//!
+//! Two words co-occur when they appear within a fixed
+//! window of each other: with a window of 2, each word
+//! co-occurs with the next two words. Such pairs are also
+//! known as skip-grams.
+
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use crate::{Analyzer, Tokenizer};
+
+/// A matrix of co-occurrence counts for pairs of words in
+/// some in-memory text documents.
+///
+/// By default counts are symmetric: the count for
+/// `("a", "b")` is the number of times `"a"` and `"b"`
+/// appear within the window in either order. Pairs do not
+/// span separate texts.
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::Cooccurrence;
+/// let matrix = Cooccurrence::new(2).extend_from_text("a b c a");
+/// assert_eq!(2, matrix.pair_count("a", "b"));
+/// assert_eq!(2, matrix.pair_count("b", "a"));
+/// assert_eq!(1, matrix.pair_count("b", "c"));
+/// assert_eq!(0, matrix.pair_count("a", "a"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cooccurrence<'a> {
+    pairs: BTreeMap<Cow<'a, str>, BTreeMap<Cow<'a, str>, usize>>,
+    analyzer: Analyzer,
+    window: usize,
+    directed: bool,
+}
+
+impl<'a> Cooccurrence<'a> {
+    /// Make a new empty matrix counting words that occur
+    /// within `window` words of each other, using the
+    /// default [Analyzer] settings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is 0.
+    pub fn new(window: usize) -> Self {
+        Self::with_analyzer(Analyzer::default(), window)
+    }
+
+    /// Make a new empty matrix counting words that occur
+    /// within `window` words of each other, using the given
+    /// `analyzer` to find words.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is 0.
+    pub fn with_analyzer(analyzer: Analyzer, window: usize) -> Self {
+        assert!(window > 0, "co-occurrence window must be at least 1");
+        Self {
+            pairs: BTreeMap::new(),
+            analyzer,
+            window,
+            directed: false,
+        }
+    }
+
+    /// Set whether counts are directed: the count for
+    /// `("a", "b")` is then the number of times `"b"`
+    /// appears within the window after `"a"`. This should
+    /// be set before any text is added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Cooccurrence;
+    /// let matrix = Cooccurrence::new(1)
+    ///     .directed(true)
+    ///     .extend_from_text("machine learning");
+    /// assert_eq!(1, matrix.pair_count("machine", "learning"));
+    /// assert_eq!(0, matrix.pair_count("learning", "machine"));
+    /// ```
+    pub fn directed(mut self, directed: bool) -> Self {
+        self.directed = directed;
+        self
+    }
+
+    /// Parse the `target` text and add the co-occurring
+    /// pairs of valid words contained in it to this matrix.
+    ///
+    /// This is a builder method: calls can be chained.
+    pub fn extend_from_text(mut self, target: &'a str) -> Self {
+        let words: Vec<_> = self.analyzer.tokenize(target).collect();
+        for (i, word) in words.iter().enumerate() {
+            for partner in words.iter().skip(i + 1).take(self.window) {
+                self.add(word.clone(), partner.clone());
+                if !self.directed && word != partner {
+                    self.add(partner.clone(), word.clone());
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Report the number of times `word` and `partner`
+    /// co-occur. Both are prepared in the same way as
+    /// keywords given to [Bbow::match_count].
+    ///
+    /// [Bbow::match_count]: crate::Bbow::match_count
+    pub fn pair_count(&self, word: &str, partner: &str) -> usize {
+        let (Some(word), Some(partner)) = (self.analyzer.query(word), self.analyzer.query(partner))
+        else {
+            return 0;
+        };
+        self.pairs
+            .get(word.as_ref())
+            .and_then(|partners| partners.get(partner.as_ref()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Return the words co-occurring with `word`, with their
+    /// counts, in alphabetical order. For a directed matrix
+    /// these are the words following `word`.
+    pub fn partners(&self, word: &str) -> impl Iterator<Item = (&str, usize)> {
+        self.analyzer
+            .query(word)
+            .and_then(|word| self.pairs.get(word.as_ref()))
+            .into_iter()
+            .flatten()
+            .map(|(partner, &count)| (partner.as_ref(), count))
+    }
+
+    /// Return up to `n` of the words co-occurring most
+    /// often with `word`, with their counts, most frequent
+    /// first. Words with equal counts are in alphabetical
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Cooccurrence;
+    /// let matrix = Cooccurrence::new(1)
+    ///     .extend_from_text("red wine, white wine, red rose, red wine");
+    /// assert_eq!(vec![("red", 3), ("white", 2)], matrix.top_partners("wine", 2));
+    /// ```
+    pub fn top_partners(&self, word: &str, n: usize) -> Vec<(&str, usize)> {
+        let mut partners: Vec<_> = self.partners(word).collect();
+        // The sort is stable, so ties stay alphabetical.
+        partners.sort_by_key(|&(_, count)| Reverse(count));
+        partners.truncate(n);
+        partners
+    }
+
+    /// Is this matrix empty?
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Count one co-occurrence of `partner` with `word`.
+    fn add(&mut self, word: Cow<'a, str>, partner: Cow<'a, str>) {
+        *self
+            .pairs
+            .entry(word)
+            .or_default()
+            .entry(partner)
+            .or_insert(0) += 1;
+    }
+}
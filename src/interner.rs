@@ -0,0 +1,192 @@
+//! Bags keyed by interned words.
+//!
+//! Many bags built from related texts hold many of the same
+//! words. An [Interner] stores each distinct word once, and
+//! an [InternedBbow] sharing it stores only a small
+//! [Symbol] for each of its words.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use lasso::{Spur, ThreadedRodeo};
+
+use crate::{Analyzer, Tokenizer};
+
+/// A compact identifier for a word stored in an
+/// [Interner].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(Spur);
+
+/// A table storing each distinct word once, identified by
+/// a [Symbol]. An interner can be shared between threads,
+/// and words can be added through a shared reference.
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::Interner;
+/// let interner = Interner::new();
+/// let hello = interner.intern("hello");
+/// assert_eq!(hello, interner.intern("hello"));
+/// assert_eq!(Some(hello), interner.get("hello"));
+/// assert_eq!("hello", interner.resolve(hello));
+/// assert_eq!(None, interner.get("world"));
+/// ```
+#[derive(Debug, Default)]
+pub struct Interner(ThreadedRodeo);
+
+impl Interner {
+    /// Make a new empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the symbol for `word`, adding it to this
+    /// interner if it is not already present.
+    pub fn intern(&self, word: &str) -> Symbol {
+        Symbol(self.0.get_or_intern(word))
+    }
+
+    /// Return the symbol for `word`, if it is present.
+    pub fn get(&self, word: &str) -> Option<Symbol> {
+        self.0.get(word).map(Symbol)
+    }
+
+    /// Return the word identified by `symbol`.
+    ///
+    /// # Panics
+    ///
+    /// A symbol made by a different interner is not
+    /// detected: this may return an unrelated word, or
+    /// panic if this interner has no word for the symbol.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        self.0.resolve(&symbol.0)
+    }
+
+    /// Count the number of words in this interner.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Is this interner empty?
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// A BBOW whose words are stored in a shared [Interner],
+/// and which itself holds only a [Symbol] and count for
+/// each word.
+///
+/// As words are not borrowed from the text, an
+/// `InternedBbow` has no lifetime parameter. Words are kept
+/// in the order they were first added to the interner.
+///
+/// # Examples
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use bbow::{InternedBbow, Interner};
+/// let interner = Arc::new(Interner::new());
+/// let first = InternedBbow::new(interner.clone()).extend_from_text("Hello world.");
+/// let second = InternedBbow::new(interner.clone()).extend_from_text("Hello, hello!");
+/// assert_eq!(1, first.match_count("hello"));
+/// assert_eq!(2, second.match_count("hello"));
+/// assert_eq!(2, interner.len());
+///
+/// let words: Vec<_> = first.words().collect();
+/// assert_eq!(vec!["hello", "world"], words);
+/// ```
+#[derive(Debug, Clone)]
+pub struct InternedBbow {
+    words: BTreeMap<Symbol, usize>,
+    analyzer: Analyzer,
+    interner: Arc<Interner>,
+}
+
+impl InternedBbow {
+    /// Make a new empty target words list storing its words
+    /// in `interner`, using the default [Analyzer]
+    /// settings.
+    pub fn new(interner: Arc<Interner>) -> Self {
+        Self::with_analyzer(Analyzer::default(), interner)
+    }
+
+    /// Make a new empty target words list storing its words
+    /// in `interner`, using the given `analyzer` to find
+    /// words.
+    pub fn with_analyzer(analyzer: Analyzer, interner: Arc<Interner>) -> Self {
+        Self {
+            words: BTreeMap::new(),
+            analyzer,
+            interner,
+        }
+    }
+
+    /// Return the interner holding the words of this BBOW.
+    pub fn interner(&self) -> &Arc<Interner> {
+        &self.interner
+    }
+
+    /// Parse the `target` text and add the sequence of
+    /// valid words contained in it to this BBOW.
+    ///
+    /// This is a builder method: calls can be chained.
+    pub fn extend_from_text(mut self, target: &str) -> Self {
+        for key in self.analyzer.tokenize(target) {
+            let symbol = self.interner.intern(&key);
+            *self.words.entry(symbol).or_insert(0) += 1;
+        }
+
+        self
+    }
+
+    /// Report the number of occurrences of the given
+    /// `keyword` that are indexed by this BBOW. The keyword
+    /// is prepared as for
+    /// [Bbow::match_count](crate::Bbow::match_count).
+    pub fn match_count(&self, keyword: &str) -> usize {
+        self.analyzer
+            .query(keyword)
+            .and_then(|key| self.interner.get(&key))
+            .map_or(0, |symbol| self.symbol_count(symbol))
+    }
+
+    /// Report the number of occurrences of the word
+    /// identified by `symbol`, which should come from this
+    /// BBOW's interner: for a symbol from another interner,
+    /// the count of an unrelated word may be returned.
+    pub fn symbol_count(&self, symbol: Symbol) -> usize {
+        self.words.get(&symbol).copied().unwrap_or(0)
+    }
+
+    /// Return the symbols of the unique words from the
+    /// sampled text(s), with their counts.
+    pub fn symbols(&self) -> impl Iterator<Item = (Symbol, usize)> + '_ {
+        self.words.iter().map(|(&symbol, &count)| (symbol, count))
+    }
+
+    /// Return the unique words from the sampled text(s).
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.words
+            .keys()
+            .map(|&symbol| self.interner.resolve(symbol))
+    }
+
+    /// Count the overall number of words contained in this
+    /// BBOW: multiple occurrences are considered separate.
+    pub fn count(&self) -> usize {
+        self.words.values().sum()
+    }
+
+    /// Count the number of unique words contained in this
+    /// BBOW, not considering number of occurrences.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Is this BBOW empty?
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
@@ -0,0 +1,115 @@
+//! Iterating over the words of a BBOW with their counts.
+
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::ops::{Bound, RangeBounds};
+
+use crate::{Bbow, Storage};
+
+/// An iterator over the words of a [Bbow] with their
+/// counts, made by [Bbow::iter].
+pub struct Iter<'s>(pub(crate) Box<dyn Iterator<Item = (&'s str, usize)> + 's>);
+
+impl<'s> Iterator for Iter<'s> {
+    type Item = (&'s str, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl<'a, S: Storage<'a>> Bbow<'a, S> {
+    /// Return the unique words from the sampled text(s),
+    /// each with its count. The words are in the same order
+    /// as for [Bbow::words].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let bbow = Bbow::new().extend_from_text("b a b");
+    /// let pairs: Vec<_> = bbow.iter().collect();
+    /// assert_eq!(vec![("a", 1), ("b", 2)], pairs);
+    /// ```
+    pub fn iter(&self) -> Iter<'_> {
+        Iter(Box::new(
+            self.words.entries().map(|(w, count)| (w.as_ref(), count)),
+        ))
+    }
+
+    /// Return the unique words from the sampled text(s),
+    /// each with its count, most frequent first. Words with
+    /// equal counts are in alphabetical order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let bbow = Bbow::new().extend_from_text("c b a b c c");
+    /// let pairs: Vec<_> = bbow.iter_by_count().collect();
+    /// assert_eq!(vec![("c", 3), ("b", 2), ("a", 1)], pairs);
+    /// ```
+    pub fn iter_by_count(&self) -> impl Iterator<Item = (&str, usize)> {
+        let mut pairs: Vec<_> = self.iter().collect();
+        pairs.sort_by_key(|&(word, count)| (Reverse(count), word));
+        pairs.into_iter()
+    }
+
+    /// Return the words in the given lexicographic `range`,
+    /// each with its count, in the same order as for
+    /// [Bbow::words].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let bbow = Bbow::new().extend_from_text("apple banana cherry banana date");
+    /// let pairs: Vec<_> = bbow.range("b".."d").collect();
+    /// assert_eq!(vec![("banana", 2), ("cherry", 1)], pairs);
+    /// let words: Vec<_> = bbow.range("cherry"..).map(|(w, _)| w).collect();
+    /// assert_eq!(vec!["cherry", "date"], words);
+    /// ```
+    pub fn range<'s, R>(&'s self, range: R) -> Iter<'s>
+    where
+        R: RangeBounds<&'s str>,
+    {
+        let bounds: (Bound<&str>, Bound<&str>) =
+            (range.start_bound().cloned(), range.end_bound().cloned());
+        Iter(Box::new(
+            self.words
+                .range(bounds)
+                .map(|(w, count)| (w.as_ref(), count)),
+        ))
+    }
+}
+
+/// Iterates over the words of a BBOW with their counts, as
+/// for [Bbow::iter].
+impl<'s, 'a, S: Storage<'a>> IntoIterator for &'s Bbow<'a, S> {
+    type Item = (&'s str, usize);
+    type IntoIter = Iter<'s>;
+
+    fn into_iter(self) -> Iter<'s> {
+        self.iter()
+    }
+}
+
+/// Consumes a BBOW, returning its words with their counts.
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::Bbow;
+/// let text = String::from("b a b");
+/// let words: Vec<_> = Bbow::new().extend_from_text(&text).into_iter().collect();
+/// assert_eq!("b", words[1].0);
+/// assert_eq!(2, words[1].1);
+/// ```
+impl<'a, S: Storage<'a>> IntoIterator for Bbow<'a, S> {
+    type Item = (Cow<'a, str>, usize);
+    type IntoIter = S::IntoIter;
+
+    fn into_iter(self) -> S::IntoIter {
+        self.words.into_iter()
+    }
+}
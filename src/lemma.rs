@@ -0,0 +1,130 @@
+//! Mapping words to canonical forms.
+//!
+//! A [LemmaTable] maps irregular forms to their lemmas
+//! (`"went"` to `"go"`, `"mice"` to `"mouse"`), or domain
+//! synonyms to a preferred spelling (`"colour"` to
+//! `"color"`), so that they are all counted under one key.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// A table mapping word forms to canonical forms.
+///
+/// Forms are looked up after case handling, so with the
+/// default [Case](crate::Case) settings the forms in the
+/// table should be lowercase.
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::{Analyzer, Bbow, LemmaTable};
+/// let mut lemmas = LemmaTable::new();
+/// lemmas.insert("went", "go");
+/// lemmas.insert("mice", "mouse");
+///
+/// let analyzer = Analyzer::new().lemmas(lemmas);
+/// let bbow = Bbow::with_analyzer(analyzer).extend_from_text("Mice go, mouse went.");
+/// assert_eq!(2, bbow.match_count("go"));
+/// assert_eq!(2, bbow.match_count("mice"));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct LemmaTable(HashMap<String, String>);
+
+impl LemmaTable {
+    /// Make a new empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read a table from tab-separated text: each line holds
+    /// a form and its canonical form, separated by a tab.
+    /// Blank lines and lines starting with `#` are ignored.
+    ///
+    /// Returns an error of kind
+    /// [io::ErrorKind::InvalidData] for a line that does
+    /// not have exactly two fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::LemmaTable;
+    /// let tsv = "# irregular forms\nwent\tgo\n\ncolour\tcolor\n";
+    /// let lemmas = LemmaTable::read_tsv(tsv.as_bytes()).unwrap();
+    /// assert_eq!(2, lemmas.len());
+    /// assert_eq!(Some("color"), lemmas.get("colour"));
+    ///
+    /// assert!(LemmaTable::read_tsv("went go\n".as_bytes()).is_err());
+    /// ```
+    pub fn read_tsv<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut table = Self::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split('\t');
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some(form), Some(canonical), None)
+                    if !form.is_empty() && !canonical.is_empty() =>
+                {
+                    table.insert(form, canonical);
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("line {}: expected form and canonical form", number + 1),
+                    ))
+                }
+            }
+        }
+        Ok(table)
+    }
+
+    /// Read a table from the tab-separated file at `path`,
+    /// as for [LemmaTable::read_tsv].
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_tsv(BufReader::new(File::open(path)?))
+    }
+
+    /// Map `form` to `canonical`, replacing any previous
+    /// mapping for `form`.
+    pub fn insert(&mut self, form: &str, canonical: &str) {
+        self.0.insert(form.to_string(), canonical.to_string());
+    }
+
+    /// Return the canonical form of `form`, if it has one.
+    pub fn get(&self, form: &str) -> Option<&str> {
+        self.0.get(form).map(String::as_str)
+    }
+
+    /// Count the number of forms in this table.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Is this table empty?
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the canonical form of the &str, borrowing if
+    /// it has none.
+    pub(crate) fn canonicalize<'w>(&self, word: &'w str) -> Cow<'w, str> {
+        match self.get(word) {
+            Some(canonical) => Cow::from(canonical.to_string()),
+            None => Cow::from(word),
+        }
+    }
+}
+
+#[test]
+fn test_canonicalize() {
+    let mut lemmas = LemmaTable::new();
+    lemmas.insert("went", "go");
+    assert_eq!(lemmas.canonicalize("went"), "go");
+    assert!(matches!(lemmas.canonicalize("go"), Cow::Borrowed("go")));
+}
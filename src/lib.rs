@@ -6,7 +6,9 @@
 //!
 //! This implementation uses zero-copy strings when
 //! reasonably possible to improve performance and reduce
-//! memory usage.
+//! memory usage. A BBOW that must outlive its texts can
+//! copy its keys with [Bbow::into_owned], or be an
+//! [ArenaBbow] or [InternedBbow] instead.
 //!
 //! Words are separated by whitespace, and consist of a
 //! span of one or more consecutive letters (any Unicode
@@ -24,67 +26,96 @@
 //! `"untïl"`, `"it"`, `"over"`.
 //!
 //! Words in the bag containing uppercase letters will be
-//! represented by their lowercase equivalent.
+//! represented by their lowercase equivalent, unless
+//! another [Case] handling is chosen.
+//!
+//! These rules are implemented by
+//! [WhitespaceLetterTokenizer], and are the default
+//! settings of the [Analyzer] used by
+//! [Bbow::extend_from_text]. A [Bbow] made with
+//! [Bbow::with_analyzer] uses the given analyzer's settings
+//! instead: for example, words with internal apostrophes
+//! or hyphens can be kept or split into parts rather than
+//! dropped, text can be Unicode-normalized so that
+//! equivalent spellings are counted as one word, and
+//! diacritics can be removed so that `"untïl"` is counted
+//! as `"until"`. Words may also be allowed to contain
+//! digits by choosing a different [TokenClass], and can be
+//! reduced to their stems in a chosen [Language] or mapped
+//! to canonical forms with a [LemmaTable]. Common words
+//! listed in a set of [StopWords] can be skipped entirely.
+//! Other rules can be used by passing a different
+//! [Tokenizer] to [Bbow::extend_with]: for example,
+//! [UnicodeWordTokenizer] finds words using the Unicode
+//! word boundary rules, so that words joined by punctuation
+//! are counted separately.
+//!
+//! Besides single words, a [NgramBbow] counts sequences of
+//! consecutive words found using the same rules, and a
+//! [CharNgramBbow] counts sequences of characters within
+//! those words. A [Cooccurrence] matrix counts pairs of
+//! words appearing near each other.
+//!
+//! A [Bbow] can also record the position of every word it
+//! counts, so that occurrences can be found in the source
+//! text, and a [Concordance] shows each occurrence of a
+//! word with the words around it.
+
+mod analyzer;
+mod arena;
+mod case;
+mod char_ngram;
+mod concordance;
+mod cooccurrence;
+mod interner;
+mod iter;
+mod lemma;
+mod multiset;
+mod ngram;
+mod normalize;
+mod occurrence;
+mod parallel;
+mod pattern;
+mod ranking;
+mod stem;
+mod stopwords;
+mod storage;
+mod tokenizer;
+
+pub use analyzer::{Analyzer, InternalPunctuation, Segmentation, TokenClass, NUMBER_PLACEHOLDER};
+pub use arena::ArenaBbow;
+pub use case::{Case, CaseLocale};
+pub use char_ngram::{CharNgramBbow, WORD_END, WORD_START};
+pub use concordance::{Concordance, ContextOrder, KwicLine};
+pub use cooccurrence::Cooccurrence;
+pub use interner::{InternedBbow, Interner, Symbol};
+pub use iter::Iter;
+pub use lemma::LemmaTable;
+pub use ngram::NgramBbow;
+pub use normalize::Normalization;
+pub use occurrence::Occurrence;
+pub use stem::Language;
+pub use stopwords::StopWords;
+pub use storage::{SortedVec, Storage};
+pub use tokenizer::{Tokenizer, UnicodeWordTokenizer, WhitespaceLetterTokenizer};
 
 use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::io::{self, BufRead};
 
 /// Each key in this struct's map is a word in some
 /// in-memory text document. The corresponding value is the
 /// count of occurrences.
+///
+/// The map is a [Storage], by default a `BTreeMap`: see
+/// [Bbow::into_storage].
 #[derive(Debug, Default, Clone)]
-pub struct Bbow<'a>(BTreeMap<Cow<'a, str>, usize>);
-
-/// Returns a bool indicating whether the &str
-/// contains any non-alphabetic characters.
-fn is_word(word: &str) -> bool {
-    !word.is_empty() && word.chars().all(|c| c.is_alphabetic())
-}
-
-#[test]
-fn test_is_word() {
-    assert_eq!(is_word("word"), true);
-    assert_eq!(is_word("Bigword"), true);
-    assert_eq!(is_word("REALLYBIGWORD"), true);
-    assert_eq!(is_word("withUnicodƹ"), true);
-    assert_eq!(is_word("not-a-word"), false);
-    assert_eq!(is_word("n0taword"), false);
-    assert_eq!(is_word("notaword!"), false);
-    assert_eq!(is_word(""), false);
-}
-
-/// Returns a bool indicating whether the &str
-/// contains any uppercase characters.
-fn has_uppercase(word: &str) -> bool {
-    word.chars().any(char::is_uppercase)
-}
-
-#[test]
-fn test_has_uppercase() {
-    assert_eq!(has_uppercase("Bigword"), true);
-    assert_eq!(has_uppercase("REALLYBIGWORD"), true);
-    assert_eq!(has_uppercase("withUnicodƹ"), true);
-    assert_eq!(has_uppercase("word"), false);
-    assert_eq!(has_uppercase("w"), false);
-    assert_eq!(has_uppercase("!"), false);
-    assert_eq!(has_uppercase(""), false);
-}
-
-/// Returns a &str with any leading or trailing characters
-/// that are *not* alphabetic removed.
-fn trim_punctuation(word: &str) -> &str {
-    // Trim any characters that are not alphabetic
-    word.trim_matches(|c: char| !c.is_alphabetic())
-}
-
-#[test]
-fn test_trim_punctuation() {
-    assert_eq!(trim_punctuation("word!"), "word");
-    assert_eq!(trim_punctuation("word?"), "word");
-    assert_eq!(trim_punctuation(".word"), "word");
-    assert_eq!(trim_punctuation("word."), "word");
-    assert_eq!(trim_punctuation("¡word"), "word");
-    assert_eq!(trim_punctuation("unicodƐ"), "unicodƐ");
+pub struct Bbow<'a, S = BTreeMap<Cow<'a, str>, usize>> {
+    words: S,
+    analyzer: Analyzer,
+    positions: Option<BTreeMap<Cow<'a, str>, Vec<Occurrence>>>,
+    texts: Vec<&'a str>,
+    text_count: usize,
 }
 
 impl<'a> Bbow<'a> {
@@ -93,6 +124,62 @@ impl<'a> Bbow<'a> {
         Self::default()
     }
 
+    /// Make a new empty target words list that uses the
+    /// given `analyzer` to find words in
+    /// [Bbow::extend_from_text].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Analyzer, Bbow, InternalPunctuation};
+    /// let analyzer = Analyzer::new().internal_punctuation(InternalPunctuation::Keep);
+    /// let bbow = Bbow::with_analyzer(analyzer).extend_from_text("It ain't over.");
+    /// assert_eq!(1, bbow.match_count("ain't"));
+    /// ```
+    pub fn with_analyzer(analyzer: Analyzer) -> Self {
+        Self {
+            analyzer,
+            ..Self::default()
+        }
+    }
+}
+
+impl<'a, S: Storage<'a>> Bbow<'a, S> {
+    /// Return the analyzer used by [Bbow::extend_from_text].
+    pub fn analyzer(&self) -> &Analyzer {
+        &self.analyzer
+    }
+
+    /// Set whether this BBOW records the position of every
+    /// occurrence of each word added by
+    /// [Bbow::extend_from_text], for use with
+    /// [Bbow::occurrences]. This should be set before any
+    /// text is added. Setting it again keeps the positions
+    /// already recorded; unsetting it discards them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let bbow = Bbow::new()
+    ///     .record_positions(true)
+    ///     .extend_from_text("a b a")
+    ///     .extend_from_text("b a");
+    /// let positions: Vec<_> = bbow.occurrences("a").map(|o| (o.text, o.index)).collect();
+    /// assert_eq!(vec![(0, 0), (0, 2), (1, 1)], positions);
+    ///
+    /// let bbow = bbow.record_positions(true).extend_from_text("a");
+    /// assert_eq!(4, bbow.occurrences("a").count());
+    /// ```
+    pub fn record_positions(mut self, record: bool) -> Self {
+        if record {
+            self.positions.get_or_insert_with(BTreeMap::new);
+        } else {
+            self.positions = None;
+        }
+        self
+    }
+
     /// Parse the `target` text and add the sequence of
     /// valid words contained in it to this BBOW.
     ///
@@ -100,6 +187,11 @@ impl<'a> Bbow<'a> {
     /// conveniently chained to build up a BBOW covering
     /// multiple texts.
     ///
+    /// Keys are borrowed from the text where possible. A
+    /// key that had to be owned, such as the lowercase
+    /// form of `"Banana"`, is replaced by a borrowed slice
+    /// when the word is later found already in key form.
+    ///
     /// # Examples
     ///
     /// ```
@@ -109,33 +201,157 @@ impl<'a> Bbow<'a> {
     /// assert_eq!(1, bbow.match_count("hello"));
     /// ```
     pub fn extend_from_text(mut self, target: &'a str) -> Self {
-        target
-            .split_whitespace()
-            // trim_punctuation gets new string slices into target
-            // that trim (remove leading or trailing) ascii punctuation
-            .map(trim_punctuation)
-            // filter removes any words that fail the is_word boolean check
-            .filter(|w| is_word(w))
-            // Return a new, owned lowercase string if an uppercase is present,
-            // otherwise return a borrowed version
-            .for_each(|w| {
-                let key = if has_uppercase(w) {
-                    Cow::from(w.to_lowercase())
-                } else {
-                    Cow::from(w)
-                };
-
-                *self.0.entry(key).or_insert(0) += 1;
-            });
+        let text = self.text_count;
+        self.text_count += 1;
+        self.texts.push(target);
+        match &mut self.positions {
+            Some(positions) => {
+                for (index, (span, key)) in self.analyzer.spans(target).enumerate() {
+                    let occurrence = Occurrence { text, index, span };
+                    entry(positions, key.clone()).push(occurrence);
+                    *self.words.counter(key) += 1;
+                }
+            }
+            None => {
+                for key in self.analyzer.tokenize(target) {
+                    *self.words.counter(key) += 1;
+                }
+            }
+        }
 
         self
     }
 
+    /// Parse the `target` text using the given `tokenizer`
+    /// and add the resulting sequence of words to this BBOW.
+    ///
+    /// Like [Bbow::extend_from_text], this is a builder
+    /// method and calls can be chained. Note that
+    /// [Bbow::match_count] still prepares its keyword using
+    /// this BBOW's [Analyzer], so the keys produced by
+    /// `tokenizer` should follow the same case rules.
+    /// Positions are not recorded for words added by this
+    /// method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Bbow, WhitespaceLetterTokenizer};
+    /// let bbow = Bbow::new()
+    ///     .extend_with(&WhitespaceLetterTokenizer, "Hello world.");
+    /// assert_eq!(2, bbow.len());
+    /// assert_eq!(1, bbow.match_count("hello"));
+    /// ```
+    pub fn extend_with<T>(mut self, tokenizer: &T, target: &'a str) -> Self
+    where
+        T: Tokenizer + ?Sized,
+    {
+        self.text_count += 1;
+        self.texts.push(target);
+        for key in tokenizer.tokenize(target) {
+            *self.words.counter(key) += 1;
+        }
+
+        self
+    }
+
+    /// Read text from `reader` line by line and add the
+    /// sequence of valid words contained in it to this BBOW,
+    /// as for [Bbow::extend_from_text]. Only one line is held
+    /// in memory at a time, so the keys added are owned
+    /// strings. The whole of the reader's text counts as a
+    /// single text, and recorded positions are byte offsets
+    /// from its start.
+    ///
+    /// Returns an error if reading fails, including an error
+    /// of kind [io::ErrorKind::InvalidData] if the text is
+    /// not valid UTF-8. Words read before the error are kept
+    /// in this BBOW.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let text = String::from("Hello world.\nHello again!\n");
+    /// let mut bbow = Bbow::new();
+    /// bbow.extend_from_reader(text.as_bytes()).unwrap();
+    /// drop(text);
+    /// assert_eq!(2, bbow.match_count("hello"));
+    /// assert_eq!(3, bbow.len());
+    ///
+    /// let mut bbow = Bbow::new();
+    /// assert!(bbow.extend_from_reader(&b"Hello world.\n\xff\n"[..]).is_err());
+    /// assert_eq!(1, bbow.match_count("hello"));
+    /// ```
+    pub fn extend_from_reader<R: BufRead>(&mut self, mut reader: R) -> io::Result<()> {
+        let text = self.text_count;
+        self.text_count += 1;
+        let mut line = String::new();
+        let (mut offset, mut index) = (0, 0);
+        while reader.read_line(&mut line)? > 0 {
+            // Keys cannot borrow from the line, so a copy is
+            // made only for words not already present.
+            for (span, key) in self.analyzer.spans(&line) {
+                if let Some(positions) = &mut self.positions {
+                    let span = offset + span.start..offset + span.end;
+                    let occurrence = Occurrence { text, index, span };
+                    match positions.get_mut(key.as_ref()) {
+                        Some(occurrences) => occurrences.push(occurrence),
+                        None => {
+                            positions.insert(owned_key(key.clone()), vec![occurrence]);
+                        }
+                    }
+                }
+                match self.words.get_mut(&key) {
+                    Some(count) => *count += 1,
+                    None => *self.words.counter(owned_key(key)) = 1,
+                }
+                index += 1;
+            }
+            offset += line.len();
+            line.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Convert this BBOW into one that owns all its keys,
+    /// so that it no longer borrows from the texts added to
+    /// it. Texts added afterwards must then be `'static`,
+    /// or read using [Bbow::extend_from_reader].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// fn count_words(text: &str) -> Bbow<'static> {
+    ///     Bbow::new().extend_from_text(text).into_owned()
+    /// }
+    ///
+    /// let bbow = count_words(&String::from("Hello world."));
+    /// assert_eq!(1, bbow.match_count("hello"));
+    /// assert_eq!(0, bbow.borrowed_keys());
+    /// ```
+    pub fn into_owned(self) -> Bbow<'static, S::Owned> {
+        Bbow {
+            words: self.words.into_owned(),
+            analyzer: self.analyzer,
+            positions: self.positions.map(owned_keys),
+            texts: Vec::new(),
+            text_count: self.text_count,
+        }
+    }
+
     /// Report the number of occurrences of the given
     /// `keyword` that are indexed by this BBOW. The keyword
     /// should be lowercase and not contain punctuation, as
     /// per the rules of BBOW: otherwise the keyword will
-    /// not match and 0 will be returned.
+    /// not match and 0 will be returned. Punctuation allowed
+    /// by this BBOW's [Analyzer] is matched as it is stored,
+    /// so `"ain’t"` matches `"ain't"`, and the keyword is
+    /// normalized, stemmed and so on in the same way as the
+    /// text. Other [Case] settings are also
+    /// applied to the keyword.
     ///
     /// # Examples:
     ///
@@ -149,15 +365,107 @@ impl<'a> Bbow<'a> {
     /// let bbow = bbow.extend_from_text("adding banana");
     /// assert_eq!(3, bbow.match_count("b"));
     /// assert_eq!(1, bbow.match_count("banana"));
+    /// assert_eq!(0, bbow.match_count("Banana"));
     /// ```
     pub fn match_count(&self, keyword: &str) -> usize {
-        match self.0.get(keyword) {
-            Some(&num) => num,
-            None => 0,
+        let Some(key) = self.analyzer.query(keyword) else {
+            return 0;
+        };
+        self.words.count(&key)
+    }
+
+    /// Return the occurrences of the given `keyword`, in the
+    /// order they were added. The keyword is prepared as for
+    /// [Bbow::match_count]. No occurrences are returned
+    /// unless this BBOW records positions: see
+    /// [Bbow::record_positions].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let text = "It ain't over until it's over.";
+    /// let bbow = Bbow::new().record_positions(true).extend_from_text(text);
+    /// let spans: Vec<_> = bbow.occurrences("over").map(|o| o.span.clone()).collect();
+    /// assert_eq!(vec![9..13, 25..29], spans);
+    /// ```
+    pub fn occurrences(&self, keyword: &str) -> impl Iterator<Item = &Occurrence> {
+        let key = self.analyzer.query(keyword);
+        self.positions
+            .as_ref()
+            .zip(key)
+            .and_then(|(positions, key)| positions.get(key.as_ref()))
+            .into_iter()
+            .flatten()
+    }
+
+    /// Replace owned keys with equal slices of the texts
+    /// added to this BBOW, wherever such a slice exists.
+    /// Unlike the replacement made when adding text, the
+    /// slice need not be a whole word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let mut bbow = Bbow::new()
+    ///     .extend_from_text("Banana!")
+    ///     .extend_from_text("bananas and apples.");
+    /// assert_eq!(1, bbow.owned_keys());
+    /// bbow.compact();
+    /// assert_eq!(0, bbow.owned_keys());
+    /// assert_eq!(1, bbow.match_count("banana"));
+    /// ```
+    pub fn compact(&mut self) {
+        let texts = &self.texts;
+        let slices: Vec<&'a str> = self
+            .words
+            .entries()
+            .map(|(key, _)| key)
+            .filter(|key| matches!(key, Cow::Owned(_)))
+            .filter_map(|key| {
+                texts.iter().find_map(|text| {
+                    let start = text.find(key.as_ref())?;
+                    Some(&text[start..start + key.len()])
+                })
+            })
+            .collect();
+        for slice in slices {
+            self.words.borrow_key(slice);
+            if let Some(positions) = &mut self.positions {
+                borrow_key(positions, slice);
+            }
         }
     }
 
+    /// Count the number of unique words whose keys are
+    /// borrowed from the texts added to this BBOW.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let bbow = Bbow::new().extend_from_text("Banana banana Apple");
+    /// assert_eq!(1, bbow.borrowed_keys());
+    /// assert_eq!(1, bbow.owned_keys());
+    /// ```
+    pub fn borrowed_keys(&self) -> usize {
+        self.len() - self.owned_keys()
+    }
+
+    /// Count the number of unique words whose keys are
+    /// owned strings, rather than borrowed from the texts
+    /// added to this BBOW.
+    pub fn owned_keys(&self) -> usize {
+        self.words
+            .entries()
+            .filter(|(key, _)| matches!(key, Cow::Owned(_)))
+            .count()
+    }
+
     /// Return the unique words from the sampled text(s).
+    /// The words are in alphabetical order unless a
+    /// `HashMap` is used for storage.
     ///
     /// # Examples:
     ///
@@ -169,8 +477,8 @@ impl<'a> Bbow<'a> {
     /// assert_eq!(Some("hello"), words.next());
     /// assert_eq!(Some("world"), words.next());
     /// ```
-    pub fn words(&'a self) -> impl Iterator<Item = &'a str> {
-        self.0.keys().map(|w| w.as_ref())
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.iter().map(|(w, _)| w)
     }
 
     /// Count the overall number of words contained in this BBOW:
@@ -185,9 +493,9 @@ impl<'a> Bbow<'a> {
     /// assert_eq!(3, bbow.count());
     /// ```
     pub fn count(&self) -> usize {
-        // Iterate over all the entries in the BTreeMap
+        // Iterate over all the entries in the storage
         // and sum the entry values
-        self.0.values().sum()
+        self.words.entries().map(|(_, count)| count).sum()
     }
 
     /// Count the number of unique words contained in this BBOW,
@@ -202,7 +510,7 @@ impl<'a> Bbow<'a> {
     /// assert_eq!(2, bbow.len());
     /// ```
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.words.len()
     }
 
     /// Is this BBOW empty?
@@ -217,6 +525,82 @@ impl<'a> Bbow<'a> {
     /// assert_eq!(true, bbow.is_empty());
     /// ```
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.words.is_empty()
     }
+
+    /// Convert this BBOW into one that stores its words in
+    /// a different [Storage]. A `HashMap` is faster to add
+    /// words to, while a [SortedVec] is smaller and quick to
+    /// search.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::collections::HashMap;
+    /// # use bbow::{Bbow, SortedVec};
+    /// let bbow = Bbow::new()
+    ///     .into_storage::<HashMap<_, _>>()
+    ///     .extend_from_text("Hello world, hello!");
+    /// assert_eq!(2, bbow.match_count("hello"));
+    ///
+    /// let bbow = bbow.into_storage::<SortedVec>();
+    /// assert_eq!(2, bbow.match_count("hello"));
+    /// assert_eq!(2, bbow.len());
+    /// ```
+    pub fn into_storage<T: Storage<'a>>(self) -> Bbow<'a, T> {
+        Bbow {
+            words: self.words.into_iter().collect(),
+            analyzer: self.analyzer,
+            positions: self.positions,
+            texts: self.texts,
+            text_count: self.text_count,
+        }
+    }
+}
+
+/// Returns the value for `key` in the map, inserting the
+/// default value if there is none. If the existing key is
+/// owned and `key` is borrowed, the key is replaced by
+/// `key`.
+fn entry<'m, 'a, V: Default>(
+    map: &'m mut BTreeMap<Cow<'a, str>, V>,
+    key: Cow<'a, str>,
+) -> &'m mut V {
+    if let Cow::Borrowed(slice) = key {
+        if let Some((Cow::Owned(_), _)) = map.get_key_value(slice) {
+            borrow_key(map, slice);
+        }
+    }
+    map.entry(key).or_default()
+}
+
+/// Returns the key as an owned string.
+fn owned_key(key: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(key.into_owned())
+}
+
+/// Returns the map with all its keys owned.
+fn owned_keys<V>(map: BTreeMap<Cow<'_, str>, V>) -> BTreeMap<Cow<'static, str>, V> {
+    map.into_iter().map(|(k, v)| (owned_key(k), v)).collect()
+}
+
+/// Replaces the key equal to `slice` in the map, if there
+/// is one, by `slice`.
+fn borrow_key<'a, V>(map: &mut BTreeMap<Cow<'a, str>, V>, slice: &'a str) {
+    if let Some(value) = map.remove(slice) {
+        map.insert(Cow::Borrowed(slice), value);
+    }
+}
+
+#[test]
+fn test_entry() {
+    let text = String::from("banana");
+    let mut map = BTreeMap::new();
+    *entry(&mut map, Cow::from(text.clone())) += 1;
+    *entry(&mut map, Cow::from(text.clone())) += 1;
+    assert!(matches!(map.keys().next(), Some(Cow::Owned(_))));
+    *entry(&mut map, Cow::from(text.as_str())) += 1;
+    *entry(&mut map, Cow::from(text.clone())) += 1;
+    assert!(matches!(map.keys().next(), Some(Cow::Borrowed(_))));
+    assert_eq!(map.get("banana"), Some(&4));
 }
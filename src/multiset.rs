@@ -0,0 +1,256 @@
+//! Combining BBOWs as multisets.
+//!
+//! A BBOW is a multiset of words: each word is present some
+//! number of times. BBOWs can be added, and combined by
+//! taking the larger, smaller or difference of the counts
+//! of each word.
+
+use std::borrow::Cow;
+use std::ops::{Add, AddAssign, Sub};
+
+use crate::{entry, Bbow, Occurrence, Storage};
+
+impl<'a, S: Storage<'a>> Bbow<'a, S> {
+    /// Return the union of this BBOW and `other`: each word
+    /// has the larger of its counts in the two. The result
+    /// uses the analyzer of this BBOW and records no
+    /// positions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let a = Bbow::new().extend_from_text("a a b");
+    /// let b = Bbow::new().extend_from_text("a c");
+    /// let union = a.union(&b);
+    /// assert_eq!(2, union.match_count("a"));
+    /// assert_eq!(1, union.match_count("c"));
+    /// assert_eq!(4, union.count());
+    /// ```
+    pub fn union<'b, T: Storage<'b>>(mut self, other: &Bbow<'b, T>) -> Self
+    where
+        'b: 'a,
+    {
+        for (key, count) in other.words.entries() {
+            let total = self.words.counter(key.clone());
+            *total = count.max(*total);
+        }
+        self.positions = None;
+        self.texts.extend(&other.texts);
+        self
+    }
+
+    /// Return the intersection of this BBOW and `other`:
+    /// each word has the smaller of its counts in the two.
+    /// The result uses the analyzer of this BBOW and records
+    /// no positions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let a = Bbow::new().extend_from_text("a a b");
+    /// let b = Bbow::new().extend_from_text("a c");
+    /// let intersection = a.intersection(&b);
+    /// assert_eq!(1, intersection.match_count("a"));
+    /// assert_eq!(1, intersection.len());
+    /// ```
+    pub fn intersection<'b, T: Storage<'b>>(self, other: &Bbow<'b, T>) -> Self {
+        self.retain_counts(|key, count| count.min(other.words.count(key)))
+    }
+
+    /// Return the difference of this BBOW and `other`: each
+    /// word has its count in this BBOW less its count in
+    /// `other`, or is removed if that is not positive. The
+    /// result uses the analyzer of this BBOW and records no
+    /// positions. This is also available as the `-`
+    /// operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let a = Bbow::new().extend_from_text("a a b");
+    /// let b = Bbow::new().extend_from_text("a b b c");
+    /// let difference = a.difference(&b);
+    /// assert_eq!(1, difference.match_count("a"));
+    /// assert_eq!(1, difference.len());
+    /// ```
+    pub fn difference<'b, T: Storage<'b>>(self, other: &Bbow<'b, T>) -> Self {
+        self.retain_counts(|key, count| count.saturating_sub(other.words.count(key)))
+    }
+
+    /// Is every word in this BBOW present at least as many
+    /// times in `other`?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let a = Bbow::new().extend_from_text("a b");
+    /// let b = Bbow::new().extend_from_text("b a c");
+    /// assert!(a.is_subset(&b));
+    /// assert!(!b.is_subset(&a));
+    /// assert!(!a.clone().extend_from_text("a").is_subset(&b));
+    /// ```
+    pub fn is_subset<'b, T: Storage<'b>>(&self, other: &Bbow<'b, T>) -> bool {
+        self.words
+            .entries()
+            .all(|(key, count)| count <= other.words.count(key))
+    }
+
+    /// Replace the count of each word by the result of `f`,
+    /// removing words whose new count is 0.
+    fn retain_counts<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str, usize) -> usize,
+    {
+        self.words = self
+            .words
+            .into_iter()
+            .filter_map(|(key, count)| {
+                let count = f(&key, count);
+                (count > 0).then_some((key, count))
+            })
+            .collect();
+        self.positions = None;
+        self
+    }
+}
+
+/// Adds the counts of the words of `rhs` to this BBOW. If
+/// both BBOWs record positions, the positions of `rhs` are
+/// kept, with its texts numbered after those of this BBOW;
+/// otherwise the result records no positions.
+impl<'a, S: Storage<'a>> AddAssign for Bbow<'a, S> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.extend(rhs.words);
+        match (&mut self.positions, rhs.positions) {
+            (Some(positions), Some(rhs_positions)) => {
+                let offset = self.text_count;
+                for (key, occurrences) in rhs_positions {
+                    let occurrences = occurrences.into_iter().map(|o| Occurrence {
+                        text: o.text + offset,
+                        ..o
+                    });
+                    entry(positions, key).extend(occurrences);
+                }
+            }
+            _ => self.positions = None,
+        }
+        self.texts.extend(rhs.texts);
+        self.text_count += rhs.text_count;
+    }
+}
+
+/// Returns the sum of two BBOWs: each word has the sum of
+/// its counts in the two. See [AddAssign].
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::Bbow;
+/// let a = Bbow::new().extend_from_text("a a b");
+/// let b = Bbow::new().extend_from_text("a c");
+/// let sum = a + b;
+/// assert_eq!(3, sum.match_count("a"));
+/// assert_eq!(5, sum.count());
+/// ```
+impl<'a, S: Storage<'a>> Add for Bbow<'a, S> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+/// Returns the difference of two BBOWs: see
+/// [Bbow::difference].
+impl<'a, S: Storage<'a>> Sub for Bbow<'a, S> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.difference(&rhs)
+    }
+}
+
+/// Adds words with their counts to this BBOW. Words with a
+/// count of 0 are not added.
+impl<'a, S: Storage<'a>> Extend<(Cow<'a, str>, usize)> for Bbow<'a, S> {
+    fn extend<I: IntoIterator<Item = (Cow<'a, str>, usize)>>(&mut self, iter: I) {
+        for (key, count) in iter {
+            if count > 0 {
+                *self.words.counter(key) += count;
+            }
+        }
+    }
+}
+
+/// Makes a BBOW holding the given words with their counts,
+/// using the default [Analyzer](crate::Analyzer) settings.
+/// The counts of repeated words are added.
+///
+/// # Examples
+///
+/// ```
+/// # use std::borrow::Cow;
+/// # use bbow::Bbow;
+/// let bbow: Bbow = [(Cow::from("a"), 2), (Cow::from("b"), 1), (Cow::from("a"), 1)]
+///     .into_iter()
+///     .collect();
+/// assert_eq!(3, bbow.match_count("a"));
+/// assert_eq!(Bbow::new().extend_from_text("a b a a"), bbow);
+/// ```
+impl<'a, S: Storage<'a>> FromIterator<(Cow<'a, str>, usize)> for Bbow<'a, S> {
+    fn from_iter<I: IntoIterator<Item = (Cow<'a, str>, usize)>>(iter: I) -> Self {
+        let mut bbow = Self::default();
+        bbow.extend(iter);
+        bbow
+    }
+}
+
+/// BBOWs are equal if they hold the same words with the
+/// same counts, whatever their storage and analyzers.
+impl<'a, 'b, S: Storage<'a>, T: Storage<'b>> PartialEq<Bbow<'b, T>> for Bbow<'a, S> {
+    fn eq(&self, other: &Bbow<'b, T>) -> bool {
+        self.len() == other.len()
+            && self
+                .words
+                .entries()
+                .all(|(key, count)| other.words.count(key) == count)
+    }
+}
+
+impl<'a, S: Storage<'a>> Eq for Bbow<'a, S> {}
+
+#[test]
+fn test_add_positions() {
+    let a = Bbow::new().record_positions(true).extend_from_text("x y");
+    let b = Bbow::new()
+        .record_positions(true)
+        .extend_from_text("y")
+        .extend_from_text("x");
+    let sum = a + b;
+    let texts: Vec<_> = sum.occurrences("x").map(|o| o.text).collect();
+    assert_eq!(texts, vec![0, 2]);
+    assert_eq!(sum.count(), 4);
+}
+
+#[test]
+fn test_add_without_positions() {
+    let a = Bbow::new().record_positions(true).extend_from_text("x");
+    let b = Bbow::new().extend_from_text("x x");
+    let sum = a + b;
+    assert_eq!(sum.match_count("x"), 3);
+    assert_eq!(sum.occurrences("x").count(), 0);
+}
+
+#[test]
+fn test_eq_counts() {
+    let a = Bbow::new().extend_from_text("a");
+    let b = Bbow::new().extend_from_text("a a");
+    assert!(a != b);
+    assert!(b != a);
+    assert!(a == Bbow::new().extend_from_text("A"));
+}
@@ -0,0 +1,179 @@
+//! Bags of word n-grams.
+//!
+//! A word n-gram is a sequence of `n` consecutive words,
+//! such as the bigram `"new york"`. Counting n-grams rather
+//! than single words lets phrases be treated as units.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+use crate::{Analyzer, Tokenizer};
+
+/// Each key in this struct's map is a sequence of
+/// consecutive words in some in-memory text document. The
+/// corresponding value is the count of occurrences.
+///
+/// N-grams of every length in a chosen range are counted
+/// in the words found by an [Analyzer]. N-grams do not span
+/// separate texts.
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::NgramBbow;
+/// let ngrams = NgramBbow::new(1..=2)
+///     .extend_from_text("New York is new.");
+/// assert_eq!(1, ngrams.match_count("new york"));
+/// assert_eq!(2, ngrams.match_count("new"));
+/// assert_eq!(0, ngrams.match_count("new york is"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct NgramBbow<'a> {
+    ngrams: BTreeMap<Vec<Cow<'a, str>>, usize>,
+    analyzer: Analyzer,
+    n: RangeInclusive<usize>,
+}
+
+impl<'a> NgramBbow<'a> {
+    /// Make a new empty n-gram list counting n-grams with
+    /// lengths in the range `n`, using the default
+    /// [Analyzer] settings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is empty or includes 0.
+    pub fn new(n: RangeInclusive<usize>) -> Self {
+        Self::with_analyzer(Analyzer::default(), n)
+    }
+
+    /// Make a new empty n-gram list counting n-grams with
+    /// lengths in the range `n`, using the given `analyzer`
+    /// to find words.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is empty or includes 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Analyzer, Language, NgramBbow, StopWords};
+    /// let stop_words = StopWords::for_language(Language::English).unwrap();
+    /// let analyzer = Analyzer::new().stop_words(stop_words);
+    /// let ngrams = NgramBbow::with_analyzer(analyzer, 2..=2)
+    ///     .extend_from_text("The machine is learning. Machine learning!");
+    /// assert_eq!(2, ngrams.match_count("machine learning"));
+    /// ```
+    pub fn with_analyzer(analyzer: Analyzer, n: RangeInclusive<usize>) -> Self {
+        assert!(
+            !n.is_empty() && *n.start() > 0,
+            "n-gram lengths must be at least 1"
+        );
+        Self {
+            ngrams: BTreeMap::new(),
+            analyzer,
+            n,
+        }
+    }
+
+    /// Parse the `target` text and add the n-grams of valid
+    /// words contained in it to this list.
+    ///
+    /// This is a builder method: calls can be chained.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::NgramBbow;
+    /// let ngrams = NgramBbow::new(1..=3)
+    ///     .extend_from_text("a b c")
+    ///     .extend_from_text("c d");
+    /// assert_eq!(1, ngrams.match_count("a b c"));
+    /// assert_eq!(1, ngrams.match_count("c d"));
+    /// assert_eq!(0, ngrams.match_count("b c d"));
+    /// ```
+    pub fn extend_from_text(mut self, target: &'a str) -> Self {
+        let words: Vec<_> = self.analyzer.tokenize(target).collect();
+        // No n-gram is longer than the text.
+        for n in *self.n.start()..=(*self.n.end()).min(words.len()) {
+            for ngram in words.windows(n) {
+                match self.ngrams.get_mut(ngram) {
+                    Some(count) => *count += 1,
+                    None => {
+                        self.ngrams.insert(ngram.to_vec(), 1);
+                    }
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Report the number of occurrences of the given
+    /// `phrase` that are indexed by this list. The phrase is
+    /// split into words by this list's [Analyzer], in the
+    /// same way as the text, and each word is prepared as
+    /// for [Bbow::match_count](crate::Bbow::match_count).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::NgramBbow;
+    /// let ngrams = NgramBbow::new(2..=2).extend_from_text("New York, new York.");
+    /// assert_eq!(2, ngrams.match_count("new york"));
+    /// assert_eq!(0, ngrams.match_count("New York"));
+    /// assert_eq!(1, ngrams.match_count("york new"));
+    /// assert_eq!(0, ngrams.match_count("york"));
+    /// ```
+    pub fn match_count(&self, phrase: &str) -> usize {
+        let words: Vec<_> = self.analyzer.query_words(phrase).collect();
+        lookup(&self.ngrams, &words)
+    }
+
+    /// Return the unique n-grams from the sampled text(s),
+    /// each as a slice of words.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::NgramBbow;
+    /// let ngrams = NgramBbow::new(2..=2).extend_from_text("a b c");
+    /// let phrases: Vec<_> = ngrams.ngrams().map(|ngram| ngram.join(" ")).collect();
+    /// assert_eq!(vec!["a b", "b c"], phrases);
+    /// ```
+    pub fn ngrams(&self) -> impl Iterator<Item = &[Cow<'a, str>]> {
+        self.ngrams.keys().map(Vec::as_slice)
+    }
+
+    /// Count the overall number of n-grams contained in
+    /// this list: multiple occurrences are considered
+    /// separate.
+    pub fn count(&self) -> usize {
+        self.ngrams.values().sum()
+    }
+
+    /// Count the number of unique n-grams contained in this
+    /// list, not considering number of occurrences.
+    pub fn len(&self) -> usize {
+        self.ngrams.len()
+    }
+
+    /// Is this list empty?
+    pub fn is_empty(&self) -> bool {
+        self.ngrams.is_empty()
+    }
+}
+
+/// Look up the count of an n-gram whose words may borrow
+/// from a shorter-lived string than the map's keys.
+fn lookup<'p>(ngrams: &BTreeMap<Vec<Cow<'p, str>>, usize>, ngram: &[Cow<'p, str>]) -> usize {
+    ngrams.get(ngram).copied().unwrap_or(0)
+}
+
+#[test]
+fn test_longer_than_text() {
+    let ngrams = NgramBbow::new(2..=usize::MAX).extend_from_text("a b c");
+    assert_eq!(ngrams.len(), 3);
+    assert_eq!(ngrams.match_count("a b c"), 1);
+}
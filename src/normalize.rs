@@ -0,0 +1,83 @@
+//! Unicode normalization of words.
+//!
+//! The same text can be spelled with different sequences
+//! of code points: `"café"` may end in a precomposed `é`,
+//! or in an `e` followed by a combining acute accent.
+//! Normalizing words before counting them lets equivalent
+//! spellings share a key. Diacritics can also be removed
+//! entirely, so that words that differ only in their
+//! accents share a key.
+
+use std::borrow::Cow;
+
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::{is_nfc, is_nfkc, UnicodeNormalization};
+
+/// The Unicode normalization form applied to text before
+/// finding words.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Normalization {
+    /// Leave text as it is.
+    #[default]
+    None,
+    /// Canonical composition (NFC): canonically equivalent
+    /// spellings, such as a precomposed `é` and `e`
+    /// followed by a combining accent, are made identical.
+    Nfc,
+    /// Compatibility composition (NFKC): as for NFC, and
+    /// compatibility variants such as the ligature `ﬁ` or
+    /// fullwidth `Ａ` are also replaced by their plain
+    /// equivalents.
+    Nfkc,
+}
+
+/// Returns the &str in the given normalization form,
+/// borrowing if it is already normalized.
+pub(crate) fn normalize(form: Normalization, text: &str) -> Cow<'_, str> {
+    match form {
+        Normalization::None => Cow::from(text),
+        Normalization::Nfc if is_nfc(text) => Cow::from(text),
+        Normalization::Nfc => Cow::from(text.nfc().collect::<String>()),
+        Normalization::Nfkc if is_nfkc(text) => Cow::from(text),
+        Normalization::Nfkc => Cow::from(text.nfkc().collect::<String>()),
+    }
+}
+
+#[test]
+fn test_normalize() {
+    let decomposed = "cafe\u{301}";
+    assert!(matches!(
+        normalize(Normalization::None, decomposed),
+        Cow::Borrowed(_)
+    ));
+    assert!(matches!(
+        normalize(Normalization::Nfc, "café"),
+        Cow::Borrowed("café")
+    ));
+    assert_eq!(normalize(Normalization::Nfc, decomposed), "café");
+    assert_eq!(normalize(Normalization::Nfc, "ﬁne"), "ﬁne");
+    assert_eq!(normalize(Normalization::Nfkc, "ﬁne"), "fine");
+}
+
+/// Returns the &str with its diacritics removed, borrowing
+/// if it has none.
+pub(crate) fn strip_diacritics(word: &str) -> Cow<'_, str> {
+    if word.nfd().any(is_combining_mark) {
+        Cow::from(
+            word.nfd()
+                .filter(|&c| !is_combining_mark(c))
+                .nfc()
+                .collect::<String>(),
+        )
+    } else {
+        Cow::from(word)
+    }
+}
+
+#[test]
+fn test_strip_diacritics() {
+    assert!(matches!(strip_diacritics("until"), Cow::Borrowed("until")));
+    assert_eq!(strip_diacritics("untïl"), "until");
+    assert_eq!(strip_diacritics("cafe\u{301}"), "cafe");
+    assert_eq!(strip_diacritics("Ærøskøbing"), "Ærøskøbing");
+}
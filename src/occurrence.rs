@@ -0,0 +1,31 @@
+//! Where words occur in a text.
+
+use std::ops::Range;
+
+/// A single occurrence of a word in a text added to a
+/// [Bbow](crate::Bbow) that records positions.
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::Bbow;
+/// let text = "Hello world, hello!";
+/// let bbow = Bbow::new().record_positions(true).extend_from_text(text);
+/// let spans: Vec<_> = bbow
+///     .occurrences("hello")
+///     .map(|o| &text[o.span.clone()])
+///     .collect();
+/// assert_eq!(vec!["Hello", "hello"], spans);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occurrence {
+    /// The number of texts added to the BBOW before the
+    /// text containing this occurrence.
+    pub text: usize,
+    /// The position of this occurrence in the sequence of
+    /// words found in the text.
+    pub index: usize,
+    /// The byte range of the text covered by this
+    /// occurrence.
+    pub span: Range<usize>,
+}
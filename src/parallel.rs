@@ -0,0 +1,129 @@
+//! Building a BBOW on several threads.
+//!
+//! A large text is split into chunks at whitespace, the
+//! words of each chunk are counted on a separate thread,
+//! and the counts are merged in order.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::thread;
+
+use crate::{Bbow, Storage, Tokenizer};
+
+impl<'a, S: Storage<'a>> Bbow<'a, S> {
+    /// Parse the `target` text and add the sequence of
+    /// valid words contained in it to this BBOW, using up to
+    /// `threads` threads. The result is identical to that
+    /// of [Bbow::extend_from_text], including which keys
+    /// are borrowed.
+    ///
+    /// If this BBOW records positions, the text is parsed
+    /// on the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let text = "the quick brown fox jumps over the lazy dog ".repeat(100);
+    /// let parallel = Bbow::new().extend_from_text_parallel(&text, 4);
+    /// let sequential = Bbow::new().extend_from_text(&text);
+    /// assert_eq!(200, parallel.match_count("the"));
+    /// assert!(parallel.words().eq(sequential.words()));
+    /// ```
+    pub fn extend_from_text_parallel(mut self, target: &'a str, threads: usize) -> Self {
+        assert!(threads > 0, "at least one thread is needed");
+        if self.positions.is_some() {
+            return self.extend_from_text(target);
+        }
+
+        let analyzer = &self.analyzer;
+        let partials: Vec<HashMap<Cow<'a, str>, usize>> = thread::scope(|scope| {
+            let handles: Vec<_> = chunks(target, threads)
+                .into_iter()
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        let mut words = HashMap::new();
+                        for key in analyzer.tokenize(chunk) {
+                            *words.counter(key) += 1;
+                        }
+                        words
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("word counting thread panicked"))
+                .collect()
+        });
+
+        self.text_count += 1;
+        self.texts.push(target);
+        // Merging in text order keeps the first borrowed key
+        // found for each word, as the sequential parse does.
+        for words in partials {
+            for (key, count) in words {
+                *self.words.counter(key) += count;
+            }
+        }
+
+        self
+    }
+}
+
+/// Returns `text` split into up to `n` chunks of similar
+/// length. Each chunk after the first starts with a
+/// whitespace character, so no word is split, and chunks
+/// may be empty only if `text` is.
+fn chunks(text: &str, n: usize) -> Vec<&str> {
+    let size = text.len().div_ceil(n).max(1);
+    let mut chunks = Vec::with_capacity(n);
+    let mut rest = text;
+    while rest.len() > size {
+        // Find the first whitespace at or after `size`.
+        let mut at = size;
+        while !rest.is_char_boundary(at) {
+            at += 1;
+        }
+        match rest[at..].find(char::is_whitespace) {
+            Some(offset) => {
+                let (chunk, tail) = rest.split_at(at + offset);
+                chunks.push(chunk);
+                rest = tail;
+            }
+            None => break,
+        }
+    }
+    chunks.push(rest);
+    chunks
+}
+
+#[test]
+fn test_chunks() {
+    assert_eq!(chunks("", 3), vec![""]);
+    assert_eq!(chunks("aa bb cc dd", 2), vec!["aa bb cc", " dd"]);
+    assert_eq!(chunks("aa bb cc dd", 4), vec!["aa bb", " cc", " dd"]);
+    assert_eq!(chunks("abcdef gh", 3), vec!["abcdef", " gh"]);
+    assert_eq!(chunks("ïïïï ï", 4), vec!["ïïïï", " ï"]);
+    assert_eq!(chunks("one", 8), vec!["one"]);
+}
+
+#[test]
+fn test_parallel_matches_sequential() {
+    use crate::{Analyzer, Segmentation};
+
+    let text = "Ünïcode wörds, ÜNÏCODE Wörds! Straße «straße» naïve-café Don't 日本語 ".repeat(7);
+    for segmentation in [Segmentation::Whitespace, Segmentation::UnicodeWords] {
+        let analyzer = Analyzer::new().segmentation(segmentation);
+        let sequential = Bbow::with_analyzer(analyzer.clone()).extend_from_text(&text);
+        for threads in 1..=5 {
+            let parallel =
+                Bbow::with_analyzer(analyzer.clone()).extend_from_text_parallel(&text, threads);
+            assert!(parallel.iter().eq(sequential.iter()));
+            assert_eq!(parallel.borrowed_keys(), sequential.borrowed_keys());
+        }
+    }
+}
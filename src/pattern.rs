@@ -0,0 +1,151 @@
+//! Finding words by prefix or wildcard pattern.
+//!
+//! Prefixes and patterns are matched against words as they
+//! are stored, after the [Analyzer](crate::Analyzer) has
+//! prepared them. Prefixes and patterns are prepared in
+//! the same way, except for the steps that apply only to
+//! whole words.
+
+use std::borrow::Cow;
+
+use crate::{Bbow, Iter, Storage};
+
+impl<'a, S: Storage<'a>> Bbow<'a, S> {
+    /// Return the words starting with `prefix`, each with
+    /// its count, in the same order as for [Bbow::words].
+    /// With sorted storage only the matching words are
+    /// visited. For other lexicographic ranges of words, see
+    /// [Bbow::range].
+    ///
+    /// The prefix is normalized, and its case and
+    /// diacritics handled, as for [Bbow::match_count]; it is
+    /// not stemmed or looked up in lemmas or stop words, as
+    /// it is not a whole word. With the default
+    /// [Case::Lowercase](crate::Case::Lowercase) it should
+    /// be lowercase.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Analyzer, Bbow, Case};
+    /// let bbow = Bbow::new().extend_from_text("Internal interest in the Internet, inter alia.");
+    /// let words: Vec<_> = bbow.words_with_prefix("inter").collect();
+    /// assert_eq!(vec![("inter", 1), ("interest", 1), ("internal", 1), ("internet", 1)], words);
+    ///
+    /// let bbow = Bbow::with_analyzer(Analyzer::new().case(Case::Fold))
+    ///     .extend_from_text("Straße, strasse und Strand");
+    /// let words: Vec<_> = bbow.words_with_prefix("STRASS").collect();
+    /// assert_eq!(vec![("strasse", 2)], words);
+    /// ```
+    pub fn words_with_prefix<'s>(&'s self, prefix: &'s str) -> Iter<'s> {
+        self.prepared_prefix(self.analyzer.query_fragment(prefix))
+    }
+
+    /// Count the overall number of occurrences of words
+    /// starting with `prefix`, which is prepared as for
+    /// [Bbow::words_with_prefix].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let bbow = Bbow::new().extend_from_text("run runs running ran runs");
+    /// assert_eq!(4, bbow.prefix_count("run"));
+    /// assert_eq!(0, bbow.prefix_count("walk"));
+    /// ```
+    pub fn prefix_count(&self, prefix: &str) -> usize {
+        self.words_with_prefix(prefix).map(|(_, count)| count).sum()
+    }
+
+    /// Return the words matching the wildcard `pattern`,
+    /// each with its count, in the same order as for
+    /// [Bbow::words]. In the pattern, `?` matches any single
+    /// character and `*` matches any sequence of
+    /// characters, including none; other characters match
+    /// themselves. The pattern is prepared as for
+    /// [Bbow::words_with_prefix].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let bbow = Bbow::new().extend_from_text("Colour, color, and colours; singing and sing.");
+    /// let words: Vec<_> = bbow.words_matching("colo?r").collect();
+    /// assert_eq!(vec![("colour", 1)], words);
+    /// let words: Vec<_> = bbow.words_matching("colo*r").map(|(w, _)| w).collect();
+    /// assert_eq!(vec!["color", "colour"], words);
+    /// let words: Vec<_> = bbow.words_matching("*ing").map(|(w, _)| w).collect();
+    /// assert_eq!(vec!["sing", "singing"], words);
+    /// ```
+    pub fn words_matching<'s>(&'s self, pattern: &'s str) -> Iter<'s> {
+        let pattern = self.analyzer.query_fragment(pattern);
+        // Only words starting with the pattern's literal
+        // prefix can match.
+        let literal = pattern.find(['?', '*']).unwrap_or(pattern.len());
+        let prefix = match &pattern {
+            Cow::Borrowed(p) => Cow::Borrowed(&p[..literal]),
+            Cow::Owned(p) => Cow::Owned(p[..literal].to_string()),
+        };
+        Iter(Box::new(
+            self.prepared_prefix(prefix)
+                .filter(move |(word, _)| glob_match(&pattern, word)),
+        ))
+    }
+
+    /// Return the words starting with `prefix`, which has
+    /// already been prepared by the analyzer.
+    fn prepared_prefix<'s>(&'s self, prefix: Cow<'s, str>) -> Iter<'s> {
+        Iter(Box::new(
+            self.words
+                .with_prefix(prefix)
+                .map(|(w, count)| (w.as_ref(), count)),
+        ))
+    }
+}
+
+/// Does `word` match the wildcard `pattern`, in which `?`
+/// matches any single char and `*` any sequence of chars?
+fn glob_match(pattern: &str, word: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let word: Vec<char> = word.chars().collect();
+    let (mut p, mut w) = (0, 0);
+    // The position of the last `*` seen, and the position
+    // in the word it is currently matched up to.
+    let mut star = None;
+    while w < word.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, w));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == word[w] => {
+                p += 1;
+                w += 1;
+            }
+            _ => match star {
+                // Let the last `*` match one more char.
+                Some((sp, sw)) => {
+                    star = Some((sp, sw + 1));
+                    p = sp + 1;
+                    w = sw + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("colo?r", "colour"));
+    assert!(!glob_match("colo?r", "color"));
+    assert!(glob_match("*ing", "sing"));
+    assert!(glob_match("*ing", "ing"));
+    assert!(!glob_match("*ing", "singer"));
+    assert!(glob_match("a*b*c", "aXbYbZc"));
+    assert!(!glob_match("a*b*c", "aXbYbZ"));
+    assert!(glob_match("*", ""));
+    assert!(glob_match("?ï*", "nïce"));
+    assert!(!glob_match("", "a"));
+}
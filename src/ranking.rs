@@ -0,0 +1,92 @@
+//! Ranking the words of a BBOW by frequency.
+//!
+//! Words are ranked by count, most frequent first. Words
+//! with equal counts are ranked in alphabetical order, so
+//! that the ranking is always the same.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::{Bbow, Storage};
+
+impl<'a, S: Storage<'a>> Bbow<'a, S> {
+    /// Return up to `k` of the most frequent words, with
+    /// their counts, most frequent first. Words with equal
+    /// counts are in alphabetical order.
+    ///
+    /// Only `k` words are held while searching, so this is
+    /// much cheaper than sorting every word when `k` is
+    /// small.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let bbow = Bbow::new().extend_from_text("c b a b c d c");
+    /// assert_eq!(vec![("c", 3), ("b", 2), ("a", 1)], bbow.most_common(3));
+    /// assert_eq!(4, bbow.most_common(10).len());
+    /// ```
+    pub fn most_common(&self, k: usize) -> Vec<(&str, usize)> {
+        let k = k.min(self.len());
+        if k == 0 {
+            return Vec::new();
+        }
+        // The top of the heap is the least frequent word
+        // kept so far, or the last alphabetically of those.
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        for (word, count) in self.words.entries() {
+            heap.push((Reverse(count), word.as_ref()));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|(Reverse(count), word)| (word, count))
+            .collect()
+    }
+
+    /// Return the position of `keyword` in the ranking of
+    /// words used by [Bbow::most_common], starting from 1,
+    /// or `None` if it is not present. The keyword is
+    /// prepared as for [Bbow::match_count].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::Bbow;
+    /// let bbow = Bbow::new().extend_from_text("c b a b c d c");
+    /// assert_eq!(Some(1), bbow.rank("c"));
+    /// assert_eq!(Some(3), bbow.rank("a"));
+    /// assert_eq!(Some(4), bbow.rank("d"));
+    /// assert_eq!(None, bbow.rank("e"));
+    /// ```
+    pub fn rank(&self, keyword: &str) -> Option<usize> {
+        let key = self.analyzer.query(keyword)?;
+        let count = self.words.count(&key);
+        if count == 0 {
+            return None;
+        }
+        let ahead = self
+            .words
+            .entries()
+            .filter(|&(word, c)| c > count || (c == count && word.as_ref() < key.as_ref()))
+            .count();
+        Some(ahead + 1)
+    }
+}
+
+#[test]
+fn test_most_common_ties() {
+    use std::collections::HashMap;
+
+    let bbow = Bbow::new()
+        .into_storage::<HashMap<_, _>>()
+        .extend_from_text("e d c b a a b c d e f");
+    assert_eq!(bbow.most_common(3), vec![("a", 2), ("b", 2), ("c", 2)]);
+    for (i, (word, _)) in bbow.most_common(6).into_iter().enumerate() {
+        assert_eq!(bbow.rank(word), Some(i + 1));
+    }
+    assert!(bbow.most_common(0).is_empty());
+    assert_eq!(bbow.most_common(usize::MAX).len(), 6);
+}
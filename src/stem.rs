@@ -0,0 +1,76 @@
+//! Reducing words to their stems.
+//!
+//! Stemming strips inflectional endings from words, so that
+//! for example `"running"`, `"runs"` and `"run"` share the
+//! key `"run"`. The Snowball stemming algorithms are used;
+//! for English this is the Porter2 algorithm.
+
+use std::borrow::Cow;
+
+use rust_stemmers::{Algorithm, Stemmer};
+
+/// A language with a Snowball stemming algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    Arabic,
+    Danish,
+    Dutch,
+    English,
+    Finnish,
+    French,
+    German,
+    Greek,
+    Hungarian,
+    Italian,
+    Norwegian,
+    Portuguese,
+    Romanian,
+    Russian,
+    Spanish,
+    Swedish,
+    Tamil,
+    Turkish,
+}
+
+impl Language {
+    /// Returns the Snowball algorithm for this language.
+    fn algorithm(self) -> Algorithm {
+        match self {
+            Language::Arabic => Algorithm::Arabic,
+            Language::Danish => Algorithm::Danish,
+            Language::Dutch => Algorithm::Dutch,
+            Language::English => Algorithm::English,
+            Language::Finnish => Algorithm::Finnish,
+            Language::French => Algorithm::French,
+            Language::German => Algorithm::German,
+            Language::Greek => Algorithm::Greek,
+            Language::Hungarian => Algorithm::Hungarian,
+            Language::Italian => Algorithm::Italian,
+            Language::Norwegian => Algorithm::Norwegian,
+            Language::Portuguese => Algorithm::Portuguese,
+            Language::Romanian => Algorithm::Romanian,
+            Language::Russian => Algorithm::Russian,
+            Language::Spanish => Algorithm::Spanish,
+            Language::Swedish => Algorithm::Swedish,
+            Language::Tamil => Algorithm::Tamil,
+            Language::Turkish => Algorithm::Turkish,
+        }
+    }
+}
+
+/// Returns the stem of the lowercase &str in the given
+/// language, borrowing if the word is its own stem.
+pub(crate) fn stem(language: Language, word: &str) -> Cow<'_, str> {
+    Stemmer::create(language.algorithm()).stem(word)
+}
+
+#[test]
+fn test_stem() {
+    assert_eq!(stem(Language::English, "running"), "run");
+    assert_eq!(stem(Language::English, "runs"), "run");
+    assert!(matches!(
+        stem(Language::English, "run"),
+        Cow::Borrowed("run")
+    ));
+    assert_eq!(stem(Language::German, "häuser"), "haus");
+}
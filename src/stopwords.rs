@@ -0,0 +1,186 @@
+//! Stopword lists.
+//!
+//! Stopwords are very common words such as `"the"`, `"and"`
+//! or `"of"` that carry little meaning on their own. An
+//! [Analyzer](crate::Analyzer) given a [StopWords] set
+//! skips them, so that they are never stored in a BBOW.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use crate::Language;
+
+/// A set of stopwords.
+///
+/// Words are checked after case handling, so with the
+/// default [Case](crate::Case) settings stopwords should
+/// be lowercase. The bundled lists are lowercase.
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::{Analyzer, Bbow, Language, StopWords};
+/// let mut stop_words = StopWords::for_language(Language::English).unwrap();
+/// stop_words.insert("rabbit");
+/// stop_words.remove("over");
+///
+/// let analyzer = Analyzer::new().stop_words(stop_words);
+/// let bbow = Bbow::with_analyzer(analyzer)
+///     .extend_from_text("The fox jumped over the rabbit and the dog.");
+/// assert_eq!(0, bbow.match_count("the"));
+/// assert_eq!(0, bbow.match_count("rabbit"));
+/// assert_eq!(1, bbow.match_count("over"));
+/// assert_eq!(4, bbow.len());
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct StopWords(HashSet<Cow<'static, str>>);
+
+impl StopWords {
+    /// Make a new empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make a set holding the bundled stopword list for
+    /// `language`, or return `None` if there is no bundled
+    /// list for it.
+    ///
+    /// Lists are bundled for Dutch, English, French, German,
+    /// Italian, Portuguese and Spanish.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bbow::{Language, StopWords};
+    /// let stop_words = StopWords::for_language(Language::French).unwrap();
+    /// assert!(stop_words.contains("les"));
+    /// assert!(StopWords::for_language(Language::Tamil).is_none());
+    /// ```
+    pub fn for_language(language: Language) -> Option<Self> {
+        let words = match language {
+            Language::Dutch => DUTCH,
+            Language::English => ENGLISH,
+            Language::French => FRENCH,
+            Language::German => GERMAN,
+            Language::Italian => ITALIAN,
+            Language::Portuguese => PORTUGUESE,
+            Language::Spanish => SPANISH,
+            _ => return None,
+        };
+        Some(Self(words.split_whitespace().map(Cow::from).collect()))
+    }
+
+    /// Add `word` to this set.
+    pub fn insert(&mut self, word: &str) {
+        self.0.insert(Cow::from(word.to_string()));
+    }
+
+    /// Remove `word` from this set, returning whether it was
+    /// present.
+    pub fn remove(&mut self, word: &str) -> bool {
+        self.0.remove(word)
+    }
+
+    /// Is `word` in this set?
+    pub fn contains(&self, word: &str) -> bool {
+        self.0.contains(word)
+    }
+
+    /// Count the number of words in this set.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Is this set empty?
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[test]
+fn test_bundled_lists() {
+    for language in [
+        Language::Dutch,
+        Language::English,
+        Language::French,
+        Language::German,
+        Language::Italian,
+        Language::Portuguese,
+        Language::Spanish,
+    ] {
+        let stop_words = StopWords::for_language(language).unwrap();
+        assert!(stop_words.0.iter().all(|w| w.to_lowercase() == *w));
+        assert!(!stop_words.is_empty());
+    }
+}
+
+// The bundled lists are based on the Snowball project's
+// stopword lists, stored as whitespace-separated words.
+
+const DUTCH: &str = "\
+    aan al alles als altijd andere ben bij daar dan dat de der deze die dit doch doen door \
+    dus een eens en er ge geen geweest haar had heb hebben heeft hem het hier hij hoe hun \
+    iemand iets ik in is ja je kan kon kunnen maar me meer men met mij mijn moet na naar \
+    niet niets nog nu of om omdat onder ons ook op over reeds te tegen toch toen tot u uit \
+    uw van veel voor want waren was wat werd wezen wie wil worden wordt zal ze zelf zich zij \
+    zijn zo zonder zou";
+
+const ENGLISH: &str = "\
+    a about above after again against all am an and any are aren't as at be because been \
+    before being below between both but by can't cannot could couldn't did didn't do does \
+    doesn't doing don't down during each few for from further had hadn't has hasn't have \
+    haven't having he he'd he'll he's her here here's hers herself him himself his how how's \
+    i i'd i'll i'm i've if in into is isn't it it's its itself let's me more most mustn't my \
+    myself no nor not of off on once only or other ought our ours ourselves out over own \
+    same shan't she she'd she'll she's should shouldn't so some such than that that's the \
+    their theirs them themselves then there there's these they they'd they'll they're \
+    they've this those through to too under until up very was wasn't we we'd we'll we're \
+    we've were weren't what what's when when's where where's which while who who's whom why \
+    why's with won't would wouldn't you you'd you'll you're you've your yours yourself \
+    yourselves";
+
+const FRENCH: &str = "\
+    au aux avec ce ces dans de des du elle en et eux il je la le les leur lui ma mais me \
+    même mes moi mon ne nos notre nous on ou par pas pour qu que qui sa se ses son sur ta te \
+    tes toi ton tu un une vos votre vous c d j l à m n s t y été étée étées étés étant suis \
+    es est sommes êtes sont serai sera serons seront serais serait étais était étions étiez \
+    étaient fut soit ai as avons avez ont aurai aura aurons auront aurait avais avait avions \
+    aviez avaient eut eu ait";
+
+const GERMAN: &str = "\
+    aber alle allem allen aller alles als also am an ander andere anderem anderen anderer \
+    anderes auch auf aus bei bin bis bist da damit dann der den des dem die das dass daß \
+    derselbe dich dir dies diese diesem diesen dieser dieses doch dort du durch ein eine \
+    einem einen einer eines einig er es etwas euch euer eure für gegen gewesen hab habe \
+    haben hat hatte hatten hier hin hinter ich ihm ihn ihnen ihr ihre im in indem ins ist \
+    jede jedem jeden jeder jedes jene jetzt kann kein keine können man manche mein meine \
+    mich mir mit muss musste nach nicht nichts noch nun nur ob oder ohne sehr sein seine \
+    sich sie sind so solche soll sondern sonst über um und uns unser unter viel vom von vor \
+    während war waren warst was weg weil weiter welche wenn werde werden wie wieder will wir \
+    wird wirst wo wollen wollte würde würden zu zum zur zwar zwischen";
+
+const ITALIAN: &str = "\
+    ad al allo ai agli all alla alle anche avere c che chi ci come con contro cui da dal \
+    dallo dai dagli dall dalla dalle del dello dei degli dell della delle di dove e è ed era \
+    erano essere gli ha hai hanno ho i il in io l la le lei li lo loro lui ma mi mia mie \
+    miei mio ne negli nei nel nella nelle nello noi non nostro o per perché più quale quanta \
+    quante quanti quanto quella quelle quelli quello questa queste questi questo se si sia \
+    siamo sono su sua sue sugli sui sul sulla sulle sullo suo suoi ti tra tu tua tue tuo \
+    tuoi tutti tutto un una uno vi voi";
+
+const PORTUGUESE: &str = "\
+    a à ao aos aquela aquelas aquele aqueles aquilo as às até com como da das de dela delas \
+    dele deles depois do dos e é ela elas ele eles em entre era eram essa essas esse esses \
+    esta está estas estava este estes eu foi for foram há isso isto já lhe lhes mais mas me \
+    mesmo meu meus minha minhas muito na não nas nem no nos nós nossa nossas nosso nossos \
+    num numa o os ou para pela pelas pelo pelos por qual quando que quem são se seja sem ser \
+    seu seus só sua suas também te tem têm tinha tu tua tuas um uma você vocês vos";
+
+const SPANISH: &str = "\
+    a al algo algunas algunos ante antes como con contra cual cuando de del desde donde \
+    durante e el él ella ellas ellos en entre era erais eran eras eres es esa esas ese eso \
+    esos esta está estaba estado estáis estamos están estar estas este esto estos estoy fue \
+    fueron fui ha había han has hay he la las le les lo los me mi mí mis mucho muchos muy \
+    más nada ni no nos nosotros nuestra nuestro o os otra otras otro otros para pero poco \
+    por porque que qué quien quienes se sea ser si sí sin sobre son su sus también tanto te \
+    tengo ti tiene todo todos tu tú tus un una uno unos vosotros y ya yo";
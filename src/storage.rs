@@ -0,0 +1,391 @@
+//! Storage for the words of a BBOW.
+//!
+//! A [Bbow](crate::Bbow) keeps its words and counts in a
+//! [Storage]. The default `BTreeMap` keeps words sorted; a
+//! `HashMap` is faster to add words to; a [SortedVec] is
+//! smaller and is best for a BBOW that is only read.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::{Bound, RangeBounds};
+
+use crate::{borrow_key, entry};
+
+/// A map from words to their counts, used to store the
+/// words of a [Bbow](crate::Bbow).
+///
+/// Implementations replace an owned key by an equal
+/// borrowed key when one is added, as described for
+/// [Bbow::extend_from_text](crate::Bbow::extend_from_text).
+pub trait Storage<'a>:
+    Default + FromIterator<(Cow<'a, str>, usize)> + IntoIterator<Item = (Cow<'a, str>, usize)>
+{
+    /// The same kind of storage with owned keys.
+    type Owned: Storage<'static>;
+
+    /// Return the count of `word`, or 0 if it is not
+    /// present.
+    fn count(&self, word: &str) -> usize;
+
+    /// Return the count of `key`, inserting a count of 0
+    /// if it is not present.
+    fn counter(&mut self, key: Cow<'a, str>) -> &mut usize;
+
+    /// Return the count of `word`, if it is present.
+    fn get_mut(&mut self, word: &str) -> Option<&mut usize>;
+
+    /// Replace the key equal to `slice`, if there is one,
+    /// by `slice`.
+    fn borrow_key(&mut self, slice: &'a str);
+
+    /// Return the words and their counts.
+    fn entries<'s>(&'s self) -> impl Iterator<Item = (&'s Cow<'a, str>, usize)>
+    where
+        'a: 's;
+
+    /// Return the words in `range`, with their counts. The
+    /// words are in alphabetical order unless the storage
+    /// is unordered.
+    fn range<'s>(
+        &'s self,
+        range: (Bound<&str>, Bound<&str>),
+    ) -> impl Iterator<Item = (&'s Cow<'a, str>, usize)>
+    where
+        'a: 's,
+    {
+        self.entries()
+            .filter(move |(key, _)| range.contains(key.as_ref()))
+    }
+
+    /// Return the words starting with `prefix`, with their
+    /// counts. The words are in alphabetical order unless
+    /// the storage is unordered.
+    fn with_prefix<'s>(
+        &'s self,
+        prefix: Cow<'s, str>,
+    ) -> impl Iterator<Item = (&'s Cow<'a, str>, usize)>
+    where
+        'a: 's,
+    {
+        self.entries()
+            .filter(move |(key, _)| key.starts_with(prefix.as_ref()))
+    }
+
+    /// Count the number of words.
+    fn len(&self) -> usize;
+
+    /// Is this storage empty?
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Convert this storage into one with owned keys.
+    fn into_owned(self) -> Self::Owned {
+        self.into_iter()
+            .map(|(k, v)| (Cow::Owned(k.into_owned()), v))
+            .collect()
+    }
+}
+
+impl<'a> Storage<'a> for BTreeMap<Cow<'a, str>, usize> {
+    type Owned = BTreeMap<Cow<'static, str>, usize>;
+
+    fn count(&self, word: &str) -> usize {
+        self.get(word).copied().unwrap_or(0)
+    }
+
+    fn counter(&mut self, key: Cow<'a, str>) -> &mut usize {
+        entry(self, key)
+    }
+
+    fn get_mut(&mut self, word: &str) -> Option<&mut usize> {
+        self.get_mut(word)
+    }
+
+    fn borrow_key(&mut self, slice: &'a str) {
+        borrow_key(self, slice);
+    }
+
+    fn entries<'s>(&'s self) -> impl Iterator<Item = (&'s Cow<'a, str>, usize)>
+    where
+        'a: 's,
+    {
+        self.iter().map(|(k, &v)| (k, v))
+    }
+
+    fn range<'s>(
+        &'s self,
+        range: (Bound<&str>, Bound<&str>),
+    ) -> impl Iterator<Item = (&'s Cow<'a, str>, usize)>
+    where
+        'a: 's,
+    {
+        // BTreeMap::range panics on these, rather than
+        // returning nothing.
+        let inverted = match range {
+            (Bound::Included(start) | Bound::Excluded(start), Bound::Included(end))
+            | (Bound::Included(start), Bound::Excluded(end)) => start > end,
+            (Bound::Excluded(start), Bound::Excluded(end)) => start >= end,
+            _ => false,
+        };
+        (!inverted)
+            .then(|| self.range::<str, _>(range))
+            .into_iter()
+            .flatten()
+            .map(|(k, &v)| (k, v))
+    }
+
+    fn with_prefix<'s>(
+        &'s self,
+        prefix: Cow<'s, str>,
+    ) -> impl Iterator<Item = (&'s Cow<'a, str>, usize)>
+    where
+        'a: 's,
+    {
+        self.range::<str, _>((Bound::Included(prefix.as_ref()), Bound::Unbounded))
+            .take_while(move |(k, _)| k.starts_with(prefix.as_ref()))
+            .map(|(k, &v)| (k, v))
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<'a> Storage<'a> for HashMap<Cow<'a, str>, usize> {
+    type Owned = HashMap<Cow<'static, str>, usize>;
+
+    fn count(&self, word: &str) -> usize {
+        self.get(word).copied().unwrap_or(0)
+    }
+
+    fn counter(&mut self, key: Cow<'a, str>) -> &mut usize {
+        if let Cow::Borrowed(slice) = key {
+            if let Some((Cow::Owned(_), _)) = self.get_key_value(slice) {
+                Storage::borrow_key(self, slice);
+            }
+        }
+        self.entry(key).or_default()
+    }
+
+    fn get_mut(&mut self, word: &str) -> Option<&mut usize> {
+        self.get_mut(word)
+    }
+
+    fn borrow_key(&mut self, slice: &'a str) {
+        if let Some(value) = self.remove(slice) {
+            self.insert(Cow::Borrowed(slice), value);
+        }
+    }
+
+    fn entries<'s>(&'s self) -> impl Iterator<Item = (&'s Cow<'a, str>, usize)>
+    where
+        'a: 's,
+    {
+        self.iter().map(|(k, &v)| (k, v))
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Words and their counts in a vector sorted by word.
+///
+/// Looking up a word takes logarithmic time, but adding a
+/// new word takes linear time, so this storage is best
+/// built by converting a finished BBOW.
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::{Bbow, SortedVec};
+/// let bbow = Bbow::new()
+///     .extend_from_text("b a b")
+///     .into_storage::<SortedVec>();
+/// assert_eq!(2, bbow.match_count("b"));
+/// let words: Vec<_> = bbow.words().collect();
+/// assert_eq!(vec!["a", "b"], words);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SortedVec<'a>(Vec<(Cow<'a, str>, usize)>);
+
+impl SortedVec<'_> {
+    /// Find the index of `word`, or the index where it
+    /// would be inserted.
+    fn search(&self, word: &str) -> Result<usize, usize> {
+        self.0.binary_search_by(|(k, _)| k.as_ref().cmp(word))
+    }
+}
+
+impl<'a> Storage<'a> for SortedVec<'a> {
+    type Owned = SortedVec<'static>;
+
+    fn count(&self, word: &str) -> usize {
+        self.search(word).map_or(0, |i| self.0[i].1)
+    }
+
+    fn counter(&mut self, key: Cow<'a, str>) -> &mut usize {
+        match self.search(&key) {
+            Ok(i) => {
+                if let (Cow::Borrowed(_), Cow::Owned(_)) = (&key, &self.0[i].0) {
+                    self.0[i].0 = key;
+                }
+                &mut self.0[i].1
+            }
+            Err(i) => {
+                self.0.insert(i, (key, 0));
+                &mut self.0[i].1
+            }
+        }
+    }
+
+    fn get_mut(&mut self, word: &str) -> Option<&mut usize> {
+        let i = self.search(word).ok()?;
+        Some(&mut self.0[i].1)
+    }
+
+    fn borrow_key(&mut self, slice: &'a str) {
+        if let Ok(i) = self.search(slice) {
+            self.0[i].0 = Cow::Borrowed(slice);
+        }
+    }
+
+    fn entries<'s>(&'s self) -> impl Iterator<Item = (&'s Cow<'a, str>, usize)>
+    where
+        'a: 's,
+    {
+        self.0.iter().map(|(k, v)| (k, *v))
+    }
+
+    fn range<'s>(
+        &'s self,
+        range: (Bound<&str>, Bound<&str>),
+    ) -> impl Iterator<Item = (&'s Cow<'a, str>, usize)>
+    where
+        'a: 's,
+    {
+        let start = self.0.partition_point(|(k, _)| match range.0 {
+            Bound::Included(start) => k.as_ref() < start,
+            Bound::Excluded(start) => k.as_ref() <= start,
+            Bound::Unbounded => false,
+        });
+        let end = self.0.partition_point(|(k, _)| match range.1 {
+            Bound::Included(end) => k.as_ref() <= end,
+            Bound::Excluded(end) => k.as_ref() < end,
+            Bound::Unbounded => true,
+        });
+        self.0[start..end.max(start)].iter().map(|(k, v)| (k, *v))
+    }
+
+    fn with_prefix<'s>(
+        &'s self,
+        prefix: Cow<'s, str>,
+    ) -> impl Iterator<Item = (&'s Cow<'a, str>, usize)>
+    where
+        'a: 's,
+    {
+        let prefix = prefix.as_ref();
+        let start = self.0.partition_point(|(k, _)| k.as_ref() < prefix);
+        let end = self
+            .0
+            .partition_point(|(k, _)| k.as_ref() < prefix || k.starts_with(prefix));
+        self.0[start..end].iter().map(|(k, v)| (k, *v))
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a> FromIterator<(Cow<'a, str>, usize)> for SortedVec<'a> {
+    fn from_iter<T: IntoIterator<Item = (Cow<'a, str>, usize)>>(iter: T) -> Self {
+        let mut words: Vec<_> = iter.into_iter().collect();
+        // Equal keys are merged, keeping a borrowed key if
+        // there is one.
+        words.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut merged: Vec<(Cow<'a, str>, usize)> = Vec::with_capacity(words.len());
+        for (key, count) in words {
+            match merged.last_mut() {
+                Some((last, total)) if *last == key => {
+                    if let (Cow::Owned(_), Cow::Borrowed(_)) = (&*last, &key) {
+                        *last = key;
+                    }
+                    *total += count;
+                }
+                _ => merged.push((key, count)),
+            }
+        }
+        merged.shrink_to_fit();
+        Self(merged)
+    }
+}
+
+impl<'a> IntoIterator for SortedVec<'a> {
+    type Item = (Cow<'a, str>, usize);
+    type IntoIter = std::vec::IntoIter<(Cow<'a, str>, usize)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[test]
+fn test_sorted_vec() {
+    let text = String::from("banana");
+    let mut words = SortedVec::default();
+    *words.counter(Cow::from("cherry")) += 1;
+    *words.counter(Cow::from(text.clone())) += 1;
+    *words.counter(Cow::from(text.as_str())) += 1;
+    assert_eq!(words.count("banana"), 2);
+    assert_eq!(words.count("apple"), 0);
+    assert!(matches!(words.0[0].0, Cow::Borrowed("banana")));
+
+    let words: SortedVec = [
+        (Cow::from("b"), 1),
+        (Cow::from("a"), 2),
+        (Cow::from(String::from("b")), 3),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(words.0, vec![(Cow::from("a"), 2), (Cow::from("b"), 4)]);
+}
+
+#[test]
+fn test_range() {
+    let words = ["apple", "banana", "cherry", "date"].map(|w| (Cow::from(w), 1));
+    let sorted: SortedVec = words.clone().into_iter().collect();
+    let hashed: HashMap<_, _> = words.clone().into_iter().collect();
+    let tree: BTreeMap<_, _> = words.clone().into_iter().collect();
+    for bounds in [
+        (Bound::Included("b"), Bound::Excluded("d")),
+        (Bound::Excluded("banana"), Bound::Unbounded),
+        (Bound::Unbounded, Bound::Included("cherry")),
+        (Bound::Included("z"), Bound::Unbounded),
+        (Bound::Included("d"), Bound::Excluded("b")),
+        (Bound::Excluded("banana"), Bound::Excluded("banana")),
+        (Bound::Excluded("banana"), Bound::Included("banana")),
+    ] {
+        let expected: Vec<_> = words
+            .iter()
+            .map(|(k, _)| k.as_ref())
+            .filter(|k| bounds.contains(*k))
+            .collect();
+        let found: Vec<_> = Storage::range(&sorted, bounds).map(|(k, _)| k).collect();
+        assert_eq!(found, expected);
+        let found: Vec<_> = Storage::range(&tree, bounds).map(|(k, _)| k).collect();
+        assert_eq!(found, expected);
+        assert_eq!(Storage::range(&hashed, bounds).count(), expected.len());
+    }
+
+    let found: Vec<_> = sorted
+        .with_prefix(Cow::from("ch"))
+        .map(|(k, _)| k)
+        .collect();
+    assert_eq!(found, vec!["cherry"]);
+    assert_eq!(sorted.with_prefix(Cow::from("e")).count(), 0);
+    assert_eq!(hashed.with_prefix(Cow::from("")).count(), 4);
+
+    let bbow = crate::Bbow::new().extend_from_text("apple banana");
+    assert_eq!(bbow.range("d".."b").count(), 0);
+}
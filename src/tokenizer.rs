@@ -0,0 +1,300 @@
+//! Splitting text into the words counted by a BBOW.
+//!
+//! A [Tokenizer] turns a source text into a sequence of
+//! words. Words are returned as [Cow] values so that a
+//! tokenizer can hand back slices of the source text when
+//! no modification is needed, and only allocate when it
+//! must (for example, to lowercase a word).
+
+use std::borrow::Cow;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A strategy for splitting text into words.
+///
+/// Implement this trait to change what counts as a word,
+/// then pass the tokenizer to
+/// [Bbow::extend_with](crate::Bbow::extend_with).
+///
+/// # Examples
+///
+/// ```
+/// # use std::borrow::Cow;
+/// # use bbow::{Bbow, Tokenizer};
+/// /// Splits on commas and keeps every field as-is.
+/// struct Fields;
+///
+/// impl Tokenizer for Fields {
+///     fn tokenize<'s, 't: 's>(
+///         &'s self,
+///         text: &'t str,
+///     ) -> Box<dyn Iterator<Item = Cow<'t, str>> + 's> {
+///         Box::new(text.split(',').map(Cow::from))
+///     }
+/// }
+///
+/// let bbow = Bbow::new().extend_with(&Fields, "a,B,a");
+/// assert_eq!(2, bbow.match_count("a"));
+/// assert_eq!(1, bbow.match_count("B"));
+/// ```
+pub trait Tokenizer {
+    /// Return the sequence of words contained in `text`,
+    /// in order of appearance. Each word should borrow
+    /// from `text` where possible.
+    fn tokenize<'s, 't: 's>(&'s self, text: &'t str)
+        -> Box<dyn Iterator<Item = Cow<'t, str>> + 's>;
+}
+
+/// The default tokenizer used by
+/// [Bbow::extend_from_text](crate::Bbow::extend_from_text).
+///
+/// Words are separated by whitespace, have leading and
+/// trailing punctuation removed, and must then consist
+/// entirely of letters. Words containing uppercase letters
+/// are returned as owned lowercase strings; all other words
+/// are borrowed from the source text.
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::{Tokenizer, WhitespaceLetterTokenizer};
+/// let words: Vec<_> = WhitespaceLetterTokenizer
+///     .tokenize("It ain't over, Over!")
+///     .collect();
+/// assert_eq!(vec!["it", "over", "over"], words);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WhitespaceLetterTokenizer;
+
+impl Tokenizer for WhitespaceLetterTokenizer {
+    fn tokenize<'s, 't: 's>(
+        &'s self,
+        text: &'t str,
+    ) -> Box<dyn Iterator<Item = Cow<'t, str>> + 's> {
+        Box::new(
+            text.split_whitespace()
+                // trim_punctuation gets new string slices into text
+                // that trim (remove leading or trailing) punctuation
+                .map(trim_punctuation)
+                // filter removes any words that fail the is_word boolean check
+                .filter(|w| is_word(w))
+                .map(lowercase),
+        )
+    }
+}
+
+/// A tokenizer that finds words using the Unicode word
+/// boundary rules of
+/// [UAX #29](https://www.unicode.org/reports/tr29/).
+///
+/// Unlike [WhitespaceLetterTokenizer], words joined by
+/// punctuation with no intervening whitespace, such as
+/// `"hello,world"`, are counted separately. Word
+/// boundaries are also found at punctuation that UAX #29
+/// would otherwise allow inside a word (`"end.Next"`),
+/// except for apostrophes. The remaining rules are the same
+/// as for [WhitespaceLetterTokenizer]: words must consist
+/// entirely of letters, and are lowercased. Words are
+/// borrowed from the source text when possible.
+///
+/// # Examples
+///
+/// ```
+/// # use bbow::{Tokenizer, UnicodeWordTokenizer};
+/// let words: Vec<_> = UnicodeWordTokenizer
+///     .tokenize("hello,world; the end.Next")
+///     .collect();
+/// assert_eq!(vec!["hello", "world", "the", "end", "next"], words);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct UnicodeWordTokenizer;
+
+impl Tokenizer for UnicodeWordTokenizer {
+    fn tokenize<'s, 't: 's>(
+        &'s self,
+        text: &'t str,
+    ) -> Box<dyn Iterator<Item = Cow<'t, str>> + 's> {
+        Box::new(
+            unicode_segments(text)
+                .map(trim_punctuation)
+                .filter(|w| is_word(w))
+                .map(lowercase),
+        )
+    }
+}
+
+/// Returns an iterator over the slices of `text` between
+/// Unicode word boundaries, with segments further split
+/// at any inner punctuation other than apostrophes.
+pub(crate) fn unicode_segments(text: &str) -> impl Iterator<Item = &str> {
+    text.split_word_bounds()
+        .flat_map(InnerPunctuationSplit::new)
+}
+
+/// Returns a bool indicating whether the char is
+/// punctuation that UAX #29 allows to join two words
+/// (the `MidLetter`, `MidNum`, `MidNumLet` and
+/// `ExtendNumLet` classes), other than an apostrophe.
+fn is_inner_punctuation(c: char) -> bool {
+    // MidLetter
+    matches!(c, ':' | '\u{B7}' | '\u{387}' | '\u{55F}' | '\u{5F4}')
+        || matches!(c, '\u{2027}' | '\u{FE13}' | '\u{FE55}' | '\u{FF1A}')
+        // MidNum
+        || matches!(c, ',' | ';' | '\u{37E}' | '\u{589}' | '\u{60C}' | '\u{60D}')
+        || matches!(c, '\u{66C}' | '\u{7F8}' | '\u{2044}' | '\u{FE10}' | '\u{FE14}')
+        || matches!(c, '\u{FE50}' | '\u{FE54}' | '\u{FF0C}' | '\u{FF1B}')
+        // MidNumLet, without the apostrophes
+        || matches!(c, '.' | '\u{2018}' | '\u{2024}' | '\u{FE52}' | '\u{FF0E}')
+        // ExtendNumLet
+        || matches!(c, '_' | '\u{202F}' | '\u{203F}' | '\u{2040}' | '\u{2054}')
+        || matches!(c, '\u{FE33}' | '\u{FE34}' | '\u{FE4D}'..='\u{FE4F}' | '\u{FF3F}')
+}
+
+/// Iterator over the pieces of a word segment separated by
+/// inner punctuation. Punctuation between two digits, as
+/// in `"3.14"`, does not separate pieces.
+struct InnerPunctuationSplit<'t> {
+    segment: &'t str,
+    start: usize,
+    done: bool,
+}
+
+impl<'t> InnerPunctuationSplit<'t> {
+    fn new(segment: &'t str) -> Self {
+        Self {
+            segment,
+            start: 0,
+            done: false,
+        }
+    }
+}
+
+impl<'t> Iterator for InnerPunctuationSplit<'t> {
+    type Item = &'t str;
+
+    fn next(&mut self) -> Option<&'t str> {
+        if self.done {
+            return None;
+        }
+        let rest = &self.segment[self.start..];
+        let mut prev = None;
+        let mut chars = rest.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            let next = chars.peek().map(|&(_, n)| n);
+            let numeric = prev.is_some_and(char::is_numeric) && next.is_some_and(char::is_numeric);
+            if is_inner_punctuation(c) && !numeric {
+                self.start += i + c.len_utf8();
+                return Some(&rest[..i]);
+            }
+            prev = Some(c);
+        }
+        self.done = true;
+        Some(rest)
+    }
+}
+
+#[test]
+fn test_unicode_segments() {
+    let segments: Vec<_> = unicode_segments("hello,world end.Next 3.14 ain't")
+        .filter(|s| !s.trim().is_empty())
+        .collect();
+    assert_eq!(
+        segments,
+        vec!["hello", "world", "end", "Next", "3.14", "ain't"]
+    );
+}
+
+/// Returns a bool indicating whether the &str
+/// contains any non-alphabetic characters.
+pub(crate) fn is_word(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(|c| c.is_alphabetic())
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn test_is_word() {
+    assert_eq!(is_word("word"), true);
+    assert_eq!(is_word("Bigword"), true);
+    assert_eq!(is_word("REALLYBIGWORD"), true);
+    assert_eq!(is_word("withUnicodƹ"), true);
+    assert_eq!(is_word("not-a-word"), false);
+    assert_eq!(is_word("n0taword"), false);
+    assert_eq!(is_word("notaword!"), false);
+    assert_eq!(is_word(""), false);
+}
+
+/// Returns a bool indicating whether the &str is a number:
+/// one or more ASCII digits, optionally separated by single `.`
+/// or `,` characters as in `"3.14"` or `"1,000"`.
+pub(crate) fn is_number(word: &str) -> bool {
+    !word.is_empty()
+        && word
+            .split(['.', ','])
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[test]
+fn test_is_number() {
+    assert!(is_number("2024"));
+    assert!(is_number("3.14"));
+    assert!(is_number("1,000"));
+    assert!(!is_number("3rd"));
+    assert!(!is_number("1..2"));
+    assert!(!is_number("1."));
+    assert!(!is_number(""));
+    assert!(!is_number("½"));
+    assert!(!is_number("x²"));
+    assert!(!is_number("Ⅻ"));
+}
+
+/// Returns a bool indicating whether the &str
+/// contains any uppercase characters.
+pub(crate) fn has_uppercase(word: &str) -> bool {
+    word.chars().any(char::is_uppercase)
+}
+
+#[test]
+#[allow(clippy::bool_assert_comparison)]
+fn test_has_uppercase() {
+    assert_eq!(has_uppercase("Bigword"), true);
+    assert_eq!(has_uppercase("REALLYBIGWORD"), true);
+    assert_eq!(has_uppercase("withUnicodƹ"), true);
+    assert_eq!(has_uppercase("word"), false);
+    assert_eq!(has_uppercase("w"), false);
+    assert_eq!(has_uppercase("!"), false);
+    assert_eq!(has_uppercase(""), false);
+}
+
+/// Returns a &str with any leading or trailing characters
+/// that are *not* alphabetic removed.
+pub(crate) fn trim_punctuation(word: &str) -> &str {
+    // Trim any characters that are not alphabetic
+    word.trim_matches(|c: char| !c.is_alphabetic())
+}
+
+#[test]
+fn test_trim_punctuation() {
+    assert_eq!(trim_punctuation("word!"), "word");
+    assert_eq!(trim_punctuation("word?"), "word");
+    assert_eq!(trim_punctuation(".word"), "word");
+    assert_eq!(trim_punctuation("word."), "word");
+    assert_eq!(trim_punctuation("¡word"), "word");
+    assert_eq!(trim_punctuation("unicodƐ"), "unicodƐ");
+}
+
+/// Return a new, owned lowercase string if an uppercase is
+/// present, otherwise return a borrowed version.
+pub(crate) fn lowercase(word: &str) -> Cow<'_, str> {
+    if has_uppercase(word) {
+        Cow::from(word.to_lowercase())
+    } else {
+        Cow::from(word)
+    }
+}
+
+#[test]
+fn test_lowercase() {
+    assert!(matches!(lowercase("word"), Cow::Borrowed("word")));
+    assert_eq!(lowercase("Word"), "word");
+    assert!(matches!(lowercase("Word"), Cow::Owned(_)));
+}